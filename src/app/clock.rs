@@ -0,0 +1,88 @@
+use std::time::{Duration, Instant};
+
+use time::{OffsetDateTime, Time, UtcOffset};
+
+use crate::cues::Schedule;
+
+pub struct ShowClock {
+    offset: UtcOffset,
+    schedule: Schedule,
+    first_go: Option<Instant>,
+    last_go: Option<Instant>,
+    act_start: Option<Instant>,
+}
+
+impl ShowClock {
+    /// Must be called before any other threads are spawned, otherwise the
+    /// local UTC offset can't be determined and the clock falls back to UTC
+    pub fn new(schedule: Schedule) -> Self {
+        let offset = UtcOffset::current_local_offset().unwrap_or_else(|_| {
+            log::warn!("Couldn't determine local time zone; clock will show UTC");
+            UtcOffset::UTC
+        });
+
+        Self {
+            offset,
+            schedule,
+            first_go: None,
+            last_go: None,
+            act_start: None,
+        }
+    }
+
+    pub fn set_schedule(&mut self, schedule: Schedule) {
+        self.schedule = schedule;
+    }
+
+    pub fn record_go(&mut self) {
+        let now = Instant::now();
+        self.first_go.get_or_insert(now);
+        self.last_go = Some(now);
+    }
+
+    pub fn start_act(&mut self) {
+        log::info!("Act timer started");
+        self.act_start = Some(Instant::now());
+    }
+
+    pub fn reset_act(&mut self) {
+        log::info!("Act timer reset");
+        self.act_start = None;
+    }
+
+    pub fn now(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc().to_offset(self.offset)
+    }
+
+    pub fn show_elapsed(&self) -> Option<Duration> {
+        self.first_go.map(|t| t.elapsed())
+    }
+
+    pub fn since_last_go(&self) -> Option<Duration> {
+        self.last_go.map(|t| t.elapsed())
+    }
+
+    pub fn act_elapsed(&self) -> Option<Duration> {
+        self.act_start.map(|t| t.elapsed())
+    }
+
+    /// The next scheduled event that hasn't happened yet, and how long until it does
+    pub fn countdown(&self) -> Option<(&'static str, Duration)> {
+        let now = self.now().time();
+
+        [
+            ("House opens", self.schedule.house_open()),
+            ("Curtain", self.schedule.curtain()),
+        ]
+        .into_iter()
+        .filter_map(|(name, t)| Some((name, until(now, t?)?)))
+        .next()
+    }
+}
+
+fn until(now: Time, then: Time) -> Option<Duration> {
+    (then - now)
+        .try_into()
+        .ok()
+        .filter(|d: &Duration| !d.is_zero())
+}
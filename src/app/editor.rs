@@ -0,0 +1,405 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::cues::{Cue, CueNumber, Expander, Script};
+use crate::prelude::FatalError;
+
+/// Fields that describe the cue itself rather than its action
+const CUE_FIELDS: [&str; 4] = ["number", "label", "description", "hint"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Number,
+    Label,
+    Description,
+    Hint,
+    Action,
+}
+
+impl Field {
+    pub const ALL: [Field; 5] = [
+        Field::Number,
+        Field::Label,
+        Field::Description,
+        Field::Hint,
+        Field::Action,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::Number => "Number",
+            Field::Label => "Label",
+            Field::Description => "Description",
+            Field::Hint => "Cue",
+            Field::Action => "Action",
+        }
+    }
+}
+
+/// A cue being inserted or edited, with its action written as one line of YAML
+pub struct CueForm {
+    index: usize,
+    replace: bool,
+    values: [String; 5],
+    focus: usize,
+    error: Option<String>,
+}
+
+impl CueForm {
+    /// Form for a new cue to be inserted at `index`, with a suggested `number`
+    pub fn insert(index: usize, number: Option<CueNumber>) -> Self {
+        let mut values: [String; 5] = Default::default();
+        values[0] = number.map(|n| n.to_string()).unwrap_or_default();
+
+        Self {
+            index,
+            replace: false,
+            values,
+            focus: 0,
+            error: None,
+        }
+    }
+
+    /// Form for changing the existing cue at `index`. Anything `expander`
+    /// would fill in from a template or the defaults is left out.
+    pub fn edit(index: usize, cue: &Cue, expander: &Expander) -> Self {
+        let action = match expander.compact_cue(cue) {
+            Ok(Value::Mapping(mut m)) => {
+                for field in CUE_FIELDS {
+                    m.remove(field);
+                }
+                flow_entries(&m)
+            }
+            _ => String::new(),
+        };
+
+        Self {
+            index,
+            replace: true,
+            values: [
+                cue.number().map(|n| n.to_string()).unwrap_or_default(),
+                cue.label().to_string(),
+                cue.description().to_string(),
+                cue.hint().to_string(),
+                action,
+            ],
+            focus: 0,
+            error: None,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn title(&self) -> &'static str {
+        if self.replace {
+            "Edit Cue"
+        } else {
+            "New Cue"
+        }
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = (Field, &str, bool)> {
+        Field::ALL
+            .into_iter()
+            .zip(&self.values)
+            .enumerate()
+            .map(|(i, (f, v))| (f, v.as_str(), i == self.focus))
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn next_field(&mut self) {
+        self.focus = (self.focus + 1) % self.values.len();
+    }
+
+    pub fn prev_field(&mut self) {
+        self.focus = (self.focus + self.values.len() - 1) % self.values.len();
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.values[self.focus].push(c);
+    }
+
+    pub fn pop(&mut self) {
+        self.values[self.focus].pop();
+    }
+
+    fn cue(&self, expander: &Expander) -> Result<Cue, String> {
+        let [number, label, description, hint, action] = &self.values;
+
+        let mut map = match serde_yaml::from_str(action).map_err(|e| e.to_string())? {
+            Value::Mapping(m) => m,
+            Value::Null => Mapping::new(),
+            _ => return Err("Action must be a mapping, e.g. `stop: all`".to_string()),
+        };
+
+        for (field, value) in CUE_FIELDS
+            .into_iter()
+            .zip([number, label, description, hint])
+        {
+            map.remove(field);
+            if field != "number" || !value.trim().is_empty() {
+                map.insert(field.into(), value.trim().into());
+            }
+        }
+
+        let mut cue = Value::Mapping(map);
+        expander.expand_cue(&mut cue).map_err(|e| e.to_string())?;
+
+        serde_yaml::from_value(cue).map_err(|e| e.to_string())
+    }
+
+    /// The cue list that results from applying this form to `cues`
+    fn apply(&self, cues: &[Cue], expander: &Expander) -> Result<Vec<Cue>, String> {
+        let mut cue = self.cue(expander)?;
+        let mut cues = cues.to_vec();
+
+        // Keep the cue in the same file as the cue it replaces or follows
+        if self.replace {
+            cue.set_source(cues[self.index].source().map(Into::into));
+            cues[self.index] = cue;
+        } else {
+            let index = self.index.min(cues.len());
+            let neighbour = index.checked_sub(1).or((!cues.is_empty()).then_some(0));
+            cue.set_source(neighbour.and_then(|i| cues[i].source()).map(Into::into));
+            cues.insert(index, cue);
+        }
+
+        Ok(cues)
+    }
+
+    /// Check the form against `script` while it's being typed. Files aren't
+    /// looked for until it's submitted.
+    pub fn check(&mut self, script: &Script) -> Option<Script> {
+        self.validate(script, Script::validate_without_files)
+    }
+
+    /// Check the form against `script`, returning the updated script if it's valid
+    pub fn submit(&mut self, script: &Script) -> Option<Script> {
+        self.validate(script, Script::validate)
+    }
+
+    fn validate(
+        &mut self,
+        script: &Script,
+        validate: fn(Script) -> Result<Script, FatalError>,
+    ) -> Option<Script> {
+        let result = script
+            .expander()
+            .map_err(|e| e.to_string())
+            .and_then(|expander| self.apply(script.cuelist(), &expander))
+            .and_then(|cues| {
+                validate(script.clone().with_cuelist(cues)).map_err(|e| e.to_string())
+            });
+
+        match result {
+            Ok(script) => {
+                self.error = None;
+                Some(script)
+            }
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+}
+
+/// Edit mode state: the open form, if any, and previous versions of the cue list
+#[derive(Default)]
+pub struct Editor {
+    active: bool,
+    dirty: bool,
+    /// Quit was pressed once with unsaved edits
+    quitting: bool,
+    undo: Vec<Vec<Cue>>,
+    form: Option<CueForm>,
+}
+
+impl Editor {
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    pub fn dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Whether quitting is waiting to be confirmed
+    pub fn quitting(&self) -> bool {
+        self.quitting
+    }
+
+    /// Returns whether it's fine to quit. With unsaved edits, quit has to be
+    /// pressed twice in a row.
+    pub fn confirm_quit(&mut self) -> bool {
+        self.quitting = self.dirty && !self.quitting;
+        !self.quitting
+    }
+
+    pub fn cancel_quit(&mut self) {
+        self.quitting = false;
+    }
+
+    pub fn toggle(&mut self) {
+        self.active = !self.active;
+        self.form = None;
+    }
+
+    pub fn form(&self) -> Option<&CueForm> {
+        self.form.as_ref()
+    }
+
+    pub fn form_mut(&mut self) -> Option<&mut CueForm> {
+        self.form.as_mut()
+    }
+
+    pub fn open(&mut self, form: CueForm) {
+        self.form = Some(form);
+    }
+
+    pub fn close(&mut self) -> Option<CueForm> {
+        self.form.take()
+    }
+
+    /// Remember `cues` so the change about to be made can be undone
+    pub fn record(&mut self, cues: &[Cue]) {
+        self.undo.push(cues.to_vec());
+        self.dirty = true;
+    }
+
+    /// `script` with the cue list from before the last edit, or `None` if
+    /// there's nothing to undo. Files aren't looked for, as when editing, and
+    /// an undo that fails is kept to try again.
+    pub fn undo(&mut self, script: &Script) -> Option<Result<Script, FatalError>> {
+        let cues = self.undo.last()?;
+        let result = script
+            .clone()
+            .with_cuelist(cues.clone())
+            .validate_without_files();
+        if result.is_ok() {
+            self.undo.pop();
+        }
+
+        Some(result)
+    }
+
+    pub fn saved(&mut self) {
+        self.dirty = false;
+        self.quitting = false;
+    }
+
+    /// Forget the undo history, e.g. after the script was changed on disk
+    pub fn reset(&mut self) {
+        self.undo.clear();
+        self.dirty = false;
+    }
+}
+
+/// Write a mapping's entries as flow-style YAML without the enclosing braces
+fn flow_entries(map: &Mapping) -> String {
+    map.iter()
+        .map(|(k, v)| format!("{}: {}", to_flow(k), to_flow(v)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn to_flow(value: &Value) -> String {
+    match value {
+        Value::Mapping(m) => format!("{{{}}}", flow_entries(m)),
+        Value::Sequence(s) => {
+            let items: Vec<_> = s.iter().map(to_flow).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Tagged(t) => format!("{} {}", t.tag, to_flow(&t.value)),
+        scalar => serde_yaml::to_string(scalar)
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::cues::actions::{FadeCue, PlaybackCue, StopCue};
+
+    #[test]
+    fn form_round_trip() {
+        let cue = Cue::new(
+            "SQ2",
+            FadeCue::new("SQ1")
+                .to_volume(40)
+                .for_duration(Duration::from_secs(3)),
+        )
+        .with_description("Fade, down");
+
+        let expander = Script::new(vec![]).expander().unwrap();
+        let form = CueForm::edit(0, &cue, &expander);
+        assert_eq!(
+            form.values[4],
+            "fade: {target: SQ1, volume: 40, duration: 3.0}"
+        );
+        assert_eq!(form.cue(&expander), Ok(cue));
+
+        let mut form = CueForm::insert(1, None);
+        form.next_field();
+        let script = Script::new(vec![Cue::new("SQ1", StopCue::new("all"))]);
+        "SQ1".chars().for_each(|c| form.push(c));
+        form.prev_field();
+        form.prev_field();
+        "stop: all".chars().for_each(|c| form.push(c));
+        assert!(form.check(&script).is_none());
+        assert_eq!(form.error(), Some("Duplicate cue label: SQ1"));
+    }
+
+    #[test]
+    fn files_checked_on_submit() {
+        let script = Script::new(vec![]);
+        let mut form = CueForm::insert(0, None);
+        form.next_field();
+        "SQ1".chars().for_each(|c| form.push(c));
+        form.prev_field();
+        form.prev_field();
+        "playback: missing.wav".chars().for_each(|c| form.push(c));
+
+        assert!(form.check(&script).is_some());
+        assert!(form.submit(&script).is_none());
+        assert_eq!(form.error(), Some("Invalid audio file in SQ1: missing.wav"));
+    }
+
+    #[test]
+    fn quit_with_unsaved_edits() {
+        let mut editor = Editor::default();
+        assert!(editor.confirm_quit());
+
+        editor.record(&[]);
+        assert!(!editor.confirm_quit());
+        assert!(editor.quitting());
+        editor.cancel_quit();
+        assert!(!editor.confirm_quit());
+        assert!(editor.confirm_quit());
+    }
+
+    #[test]
+    fn undo_without_files() {
+        let before = vec![Cue::new("SQ1", PlaybackCue::new("missing.wav"))];
+        let script = Script::new(vec![]);
+        let mut editor = Editor::default();
+
+        editor.record(&before);
+        let undone = editor.undo(&script).unwrap().unwrap();
+        assert_eq!(undone.cuelist(), before.as_slice());
+        assert!(editor.undo(&script).is_none());
+
+        // A failed undo can be tried again
+        let stop = Cue::new("SQ1", StopCue::new("all"));
+        editor.record(&[stop.clone(), stop]);
+        assert!(editor.undo(&script).unwrap().is_err());
+        assert!(editor.undo(&script).unwrap().is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::time::{Duration, Instant};
+
+use crate::cues::GoSettings;
+
+/// How long the cue list highlight flashes after a GO was ignored
+const IGNORED_FLASH: Duration = Duration::from_millis(400);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoAction {
+    Fire,
+    Standby,
+    Ignored,
+}
+
+pub struct GoGuard {
+    settings: GoSettings,
+    last_go: Option<Instant>,
+    /// `last_go` before the latest GO, to go back to if it couldn't fire
+    previous_go: Option<Instant>,
+    standby: Option<usize>,
+    ignored: Option<Instant>,
+}
+
+impl GoGuard {
+    pub fn new(settings: GoSettings) -> Self {
+        Self {
+            settings,
+            last_go: None,
+            previous_go: None,
+            standby: None,
+            ignored: None,
+        }
+    }
+
+    /// Decide what a press of the GO button should do for the cue at `selected`
+    pub fn press(&mut self, selected: usize) -> GoAction {
+        let now = Instant::now();
+
+        if self
+            .last_go
+            .is_some_and(|t| now.duration_since(t) < self.settings.lockout())
+        {
+            self.ignored = Some(now);
+            return GoAction::Ignored;
+        }
+
+        if self.settings.standby() && self.standby != Some(selected) {
+            self.standby = Some(selected);
+            return GoAction::Standby;
+        }
+
+        self.standby = None;
+        self.previous_go = self.last_go.replace(now);
+        GoAction::Fire
+    }
+
+    /// Undo the last GO of the cue at `selected` because the cue couldn't
+    /// fire, so the operator can press GO again straight away
+    pub fn reject(&mut self, selected: usize) {
+        self.last_go = self.previous_go.take();
+        self.ignored = Some(Instant::now());
+        if self.settings.standby() {
+            self.standby = Some(selected);
+        }
+    }
+
+    /// Replace the settings, e.g. after the script is reloaded. Any standby is cleared.
+    pub fn set_settings(&mut self, settings: GoSettings) {
+        self.settings = settings;
+        self.standby = None;
+    }
+
+    pub fn clear_standby(&mut self) {
+        self.standby = None;
+    }
+
+    pub fn standby(&self) -> Option<usize> {
+        self.standby
+    }
+
+    pub fn flashing(&self) -> bool {
+        self.ignored.is_some_and(|t| t.elapsed() < IGNORED_FLASH)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread, time::Duration};
+
+    use super::{GoAction, GoGuard};
+    use crate::app::loader::{CueSlots, NotReady};
+    use crate::app::state::LoadStatus;
+    use crate::cues::{actions::StopCue, Cue, GoSettings, Script};
+
+    #[test]
+    fn lockout_window() {
+        let mut go = GoGuard::new(GoSettings::default().with_lockout(Duration::from_millis(50)));
+
+        assert_eq!(go.press(0), GoAction::Fire);
+        assert_eq!(go.press(1), GoAction::Ignored);
+        assert!(go.flashing());
+
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(go.press(1), GoAction::Fire);
+    }
+
+    #[test]
+    fn standby_then_go() {
+        let mut go = GoGuard::new(
+            GoSettings::default()
+                .with_lockout(Duration::ZERO)
+                .with_standby(),
+        );
+
+        assert_eq!(go.press(0), GoAction::Standby);
+        assert_eq!(go.standby(), Some(0));
+        assert_eq!(go.press(0), GoAction::Fire);
+        assert_eq!(go.standby(), None);
+
+        // Moving the selection while standing by needs a new standby
+        assert_eq!(go.press(1), GoAction::Standby);
+        assert_eq!(go.press(2), GoAction::Standby);
+        go.clear_standby();
+        assert_eq!(go.press(2), GoAction::Standby);
+        assert_eq!(go.press(2), GoAction::Fire);
+    }
+
+    #[test]
+    fn go_on_unloaded_cue_does_not_fire() {
+        let script = Script::new(
+            (1..=20)
+                .map(|i| Cue::new(format!("SQ{i}"), StopCue::new("all")))
+                .collect(),
+        );
+        let mut slots = CueSlots::new(&script, 0);
+        let mut go = GoGuard::new(GoSettings::default().with_lockout(Duration::from_secs(60)));
+
+        // Far outside the window around the first cue
+        let selected = 15;
+        assert_eq!(slots.status(selected), LoadStatus::NotLoaded);
+        assert_eq!(go.press(selected), GoAction::Fire);
+        assert_eq!(
+            slots.take(&script, selected).err(),
+            Some(NotReady::NotLoaded)
+        );
+        go.reject(selected);
+        assert!(go.flashing());
+
+        // Not locked out, and the cue is loading now, so the next GO fires it
+        slots.focus(&script, selected);
+        while slots.status(selected) != LoadStatus::Loaded {
+            thread::sleep(Duration::from_millis(5));
+            slots.finish_loads();
+        }
+        assert_eq!(slots.status(0), LoadStatus::NotLoaded);
+        assert_eq!(go.press(selected), GoAction::Fire);
+        assert!(slots.take(&script, selected).is_ok());
+        assert_eq!(go.press(selected + 1), GoAction::Ignored);
+    }
+}
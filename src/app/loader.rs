@@ -0,0 +1,394 @@
+use std::{
+    ops::Range,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Condvar, Mutex,
+    },
+    thread,
+};
+
+use crate::cues::{Cue, CueAction, Script};
+use crate::sound::ExecutableCue;
+
+use super::state::LoadStatus;
+
+/// Upper limit on the number of loader threads
+const MAX_WORKERS: usize = 4;
+
+/// Cues behind the selected cue are this many times less urgent than cues ahead of it
+const BEHIND_PENALTY: usize = 4;
+
+/// Number of cues after the selected one that are kept loaded
+const WINDOW_AHEAD: usize = 8;
+
+/// Number of cues before the selected one that are kept loaded, so stepping
+/// back to one doesn't have to wait
+const WINDOW_BEHIND: usize = 2;
+
+pub struct LoadResult {
+    pub generation: u64,
+    pub result: Result<ExecutableCue, String>,
+}
+
+struct Job {
+    index: usize,
+    generation: u64,
+    label: String,
+    action: CueAction,
+}
+
+#[derive(Default)]
+struct Queue {
+    jobs: Vec<Job>,
+    shutdown: bool,
+}
+
+impl Queue {
+    /// Remove the job closest to (and preferably after) the selected cue
+    fn pop_nearest(&mut self, focus: usize) -> Option<Job> {
+        let distance = |index: usize| {
+            if index >= focus {
+                index - focus
+            } else {
+                (focus - index) * BEHIND_PENALTY
+            }
+        };
+
+        let (i, _) = self
+            .jobs
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, job)| distance(job.index))?;
+
+        Some(self.jobs.swap_remove(i))
+    }
+}
+
+/// Pool of threads that prepare cues in the background, prioritizing the
+/// cues nearest the current selection
+pub struct CueLoader {
+    queue: Arc<(Mutex<Queue>, Condvar)>,
+    focus: Arc<AtomicUsize>,
+    next_generation: u64,
+    results: mpsc::Receiver<LoadResult>,
+    workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl CueLoader {
+    pub fn new() -> Self {
+        let queue: Arc<(Mutex<Queue>, Condvar)> = Default::default();
+        let focus = Arc::new(AtomicUsize::new(0));
+        let (tx, results) = mpsc::channel();
+
+        let count = thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(MAX_WORKERS);
+
+        let workers = (0..count)
+            .map(|_| {
+                let queue = queue.clone();
+                let focus = focus.clone();
+                let tx = tx.clone();
+
+                thread::spawn(move || work(&queue, &focus, &tx))
+            })
+            .collect();
+
+        Self {
+            queue,
+            focus,
+            next_generation: 0,
+            results,
+            workers,
+        }
+    }
+
+    /// Queue `cue`, currently at `index` in the cue list, to be prepared,
+    /// replacing any job still queued for that index.
+    /// Returns the generation that the result will be tagged with.
+    pub fn request(&mut self, index: usize, cue: &Cue) -> u64 {
+        self.next_generation += 1;
+        let generation = self.next_generation;
+
+        let (lock, cvar) = &*self.queue;
+        if let Ok(mut queue) = lock.lock() {
+            queue.jobs.retain(|job| job.index != index);
+            queue.jobs.push(Job {
+                index,
+                generation,
+                label: cue.label().to_string(),
+                action: cue.action().clone(),
+            });
+            cvar.notify_one();
+        }
+
+        generation
+    }
+
+    /// Prioritize loading the cues around `index`
+    pub fn focus(&self, index: usize) {
+        self.focus.store(index, Ordering::Relaxed);
+    }
+
+    /// Drop queued jobs for cues outside `window`
+    pub fn retain(&self, window: &Range<usize>) {
+        let (lock, _) = &*self.queue;
+        if let Ok(mut queue) = lock.lock() {
+            queue.jobs.retain(|job| window.contains(&job.index));
+        }
+    }
+
+    /// Drop all queued jobs, e.g. when the cue list has changed under them
+    pub fn clear(&self) {
+        let (lock, _) = &*self.queue;
+        if let Ok(mut queue) = lock.lock() {
+            queue.jobs.clear();
+        }
+    }
+
+    /// Results of all jobs finished since the last call
+    pub fn results(&self) -> impl Iterator<Item = LoadResult> + '_ {
+        self.results.try_iter()
+    }
+}
+
+impl Drop for CueLoader {
+    fn drop(&mut self) {
+        let (lock, cvar) = &*self.queue;
+        if let Ok(mut queue) = lock.lock() {
+            queue.shutdown = true;
+            queue.jobs.clear();
+        }
+        cvar.notify_all();
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[derive(Default)]
+enum LazyExecutable {
+    Ready(ExecutableCue),
+    Error(String),
+    Loading(u64),
+    #[default]
+    NotLoaded,
+}
+
+impl LazyExecutable {
+    fn status(&self) -> LoadStatus {
+        match self {
+            LazyExecutable::Ready(_) => LoadStatus::Loaded,
+            LazyExecutable::Error(e) => LoadStatus::Failed(e.clone()),
+            LazyExecutable::Loading(_) => LoadStatus::Loading,
+            LazyExecutable::NotLoaded => LoadStatus::NotLoaded,
+        }
+    }
+
+    /// Whether this slot is waiting on the load request tagged `generation`
+    fn awaits(&self, generation: u64) -> bool {
+        matches!(self, Self::Loading(g) if *g == generation)
+    }
+
+    fn finish(&mut self, result: LoadResult) {
+        *self = match result.result {
+            Ok(exe) => LazyExecutable::Ready(exe),
+            Err(e) => LazyExecutable::Error(e),
+        }
+    }
+}
+
+/// Why a cue couldn't be fired straight away
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum NotReady {
+    #[error("it's still loading")]
+    Loading,
+
+    #[error("it failed to load: {0}")]
+    Failed(String),
+
+    #[error("it isn't loaded yet")]
+    NotLoaded,
+}
+
+/// The prepared executable of every cue in the cue list. Only the cues in a
+/// window around the selection are loaded, in the background.
+pub struct CueSlots {
+    slots: Vec<LazyExecutable>,
+    loader: CueLoader,
+}
+
+impl CueSlots {
+    /// Start loading the cues around `focus` in `script`
+    pub fn new(script: &Script, focus: usize) -> Self {
+        let mut slots = Self {
+            slots: Vec::new(),
+            loader: CueLoader::new(),
+        };
+        slots
+            .slots
+            .resize_with(script.cuelist().len(), Default::default);
+        slots.focus(script, focus);
+        slots
+    }
+
+    pub fn status(&self, index: usize) -> LoadStatus {
+        self.slots
+            .get(index)
+            .map_or(LoadStatus::NotLoaded, LazyExecutable::status)
+    }
+
+    /// Number of cues in the window that have finished loading (successfully or not), and the window size
+    pub fn progress(&self) -> (usize, usize) {
+        let pending = self
+            .slots
+            .iter()
+            .filter(|s| matches!(s, LazyExecutable::Loading(_)))
+            .count();
+        let loaded = self
+            .slots
+            .iter()
+            .filter(|s| matches!(s, LazyExecutable::Ready(_) | LazyExecutable::Error(_)))
+            .count();
+
+        (loaded, loaded + pending)
+    }
+
+    /// Load the cues around `index`, nearest first, and unload the rest
+    pub fn focus(&mut self, script: &Script, index: usize) {
+        self.loader.focus(index);
+
+        let window = window(index, self.slots.len());
+        for (i, slot) in self.slots.iter_mut().enumerate() {
+            if !window.contains(&i) {
+                *slot = LazyExecutable::NotLoaded;
+            }
+        }
+        self.loader.retain(&window);
+
+        for i in window {
+            self.request(script, i);
+        }
+    }
+
+    /// Load the cue at `index` unless it's loaded or loading already. Failed cues are tried again.
+    pub fn request(&mut self, script: &Script, index: usize) {
+        let Some((slot, cue)) = self.slots.get_mut(index).zip(script.resolve(index)) else {
+            return;
+        };
+
+        if matches!(slot, LazyExecutable::Ready(_) | LazyExecutable::Loading(_)) {
+            return;
+        }
+
+        log::debug!("Loading cue {}", cue.label());
+        *slot = LazyExecutable::Loading(self.loader.request(index, &cue));
+    }
+
+    /// Take the executable for the cue at `index` to fire it, queueing a fresh
+    /// copy so the cue can be fired again. Never waits for a load.
+    pub fn take(&mut self, script: &Script, index: usize) -> Result<ExecutableCue, NotReady> {
+        let slot = self.slots.get_mut(index).ok_or(NotReady::NotLoaded)?;
+
+        let result = match std::mem::take(slot) {
+            LazyExecutable::Ready(exe) => Ok(exe),
+            LazyExecutable::Loading(generation) => {
+                *slot = LazyExecutable::Loading(generation);
+                return Err(NotReady::Loading);
+            }
+            LazyExecutable::Error(e) => Err(NotReady::Failed(e)),
+            LazyExecutable::NotLoaded => Err(NotReady::NotLoaded),
+        };
+
+        self.request(script, index);
+        result
+    }
+
+    /// Store the results of loads that have finished
+    pub fn finish_loads(&mut self) {
+        for result in self.loader.results() {
+            // Results for cues that have since been reloaded or unloaded are dropped
+            if let Some(slot) = self.slots.iter_mut().find(|s| s.awaits(result.generation)) {
+                slot.finish(result);
+            }
+        }
+    }
+
+    /// Switch to a new version of the script, keeping what's loaded for cues
+    /// that haven't changed between `old` and the new script
+    pub fn replace(&mut self, old: &Script, script: &Script, focus: usize) {
+        // Compare cues with section defaults applied, so changing a section's
+        // defaults reloads the cues in it
+        let old_cues = old.resolved_cuelist();
+        let mut old: std::collections::HashMap<_, _> = old_cues
+            .iter()
+            .zip(std::mem::take(&mut self.slots))
+            .map(|(cue, slot)| (cue.label(), (cue, slot)))
+            .collect();
+
+        self.slots = script
+            .resolved_cuelist()
+            .iter()
+            .map(|cue| match old.remove(cue.label()) {
+                Some((old_cue, slot @ LazyExecutable::Ready(_))) if old_cue == cue => slot,
+                _ => LazyExecutable::NotLoaded,
+            })
+            .collect();
+
+        // Queued jobs are for the old indices and nothing awaits them anymore
+        self.loader.clear();
+
+        self.focus(script, focus);
+    }
+}
+
+/// Indices of the cues kept loaded when `focus` is selected
+fn window(focus: usize, len: usize) -> Range<usize> {
+    focus.saturating_sub(WINDOW_BEHIND)..(focus + WINDOW_AHEAD + 1).min(len)
+}
+
+fn work(queue: &(Mutex<Queue>, Condvar), focus: &AtomicUsize, tx: &mpsc::Sender<LoadResult>) {
+    let (lock, cvar) = queue;
+
+    loop {
+        let job = {
+            let Ok(mut queue) = lock.lock() else {
+                return;
+            };
+
+            loop {
+                if queue.shutdown {
+                    return;
+                }
+
+                if let Some(job) = queue.pop_nearest(focus.load(Ordering::Relaxed)) {
+                    break job;
+                }
+
+                queue = match cvar.wait(queue) {
+                    Ok(q) => q,
+                    Err(_) => return,
+                };
+            }
+        };
+
+        let result = job.action.prepare(Some(&job.label)).map_err(|e| {
+            log::error!("Error preparing cue `{}`: {e}", job.label);
+            e.to_string()
+        });
+
+        if result.is_ok() {
+            log::debug!("Loaded cue `{}`", job.label);
+        }
+
+        let sent = tx.send(LoadResult {
+            generation: job.generation,
+            result,
+        });
+
+        if sent.is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use log::LevelFilter;
+
+use crate::logging::{LogFilter, TuiLoggerState};
+
+/// How the log pane is shown: whether it has focus, what it's filtered to
+/// and how far it's scrolled back
+#[derive(Default)]
+pub struct LogPane {
+    focused: bool,
+    fullscreen: bool,
+    filter: LogFilter,
+    /// Id of the entry at the bottom of the pane, or `None` to follow new entries
+    anchor: Option<u64>,
+    /// Search text being typed, before it's applied
+    search: Option<String>,
+    /// Lines of log the pane had room for when it was last drawn
+    height: usize,
+}
+
+impl LogPane {
+    pub fn focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+
+    pub fn filter(&self) -> &LogFilter {
+        &self.filter
+    }
+
+    pub fn anchor(&self) -> Option<u64> {
+        self.anchor
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn set_height(&mut self, height: usize) {
+        self.height = height;
+    }
+
+    pub fn search_input(&mut self) -> Option<&mut String> {
+        self.search.as_mut()
+    }
+
+    pub fn search_prompt(&self) -> Option<&str> {
+        self.search.as_deref()
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+        if !self.focused {
+            self.fullscreen = false;
+        }
+    }
+
+    /// Fullscreen always has focus, so it can be scrolled straight away
+    pub fn toggle_fullscreen(&mut self) {
+        self.fullscreen = !self.fullscreen;
+        self.focused |= self.fullscreen;
+    }
+
+    /// Hide the least important level, or show everything again once only errors are left
+    pub fn cycle_level(&mut self) {
+        self.filter.level = match self.filter.level {
+            LevelFilter::Off | LevelFilter::Trace => LevelFilter::Debug,
+            LevelFilter::Debug => LevelFilter::Info,
+            LevelFilter::Info => LevelFilter::Warn,
+            LevelFilter::Warn => LevelFilter::Error,
+            LevelFilter::Error => LevelFilter::Trace,
+        };
+        self.anchor = None;
+    }
+
+    pub fn start_search(&mut self) {
+        self.search = Some(self.filter.search().to_string());
+    }
+
+    pub fn submit_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            self.filter.set_search(search.trim());
+            self.anchor = None;
+        }
+    }
+
+    /// Stop typing, and stop searching altogether
+    pub fn cancel_search(&mut self) {
+        self.search = None;
+        self.filter.set_search("");
+        self.anchor = None;
+    }
+
+    /// Move the view `lines` towards older entries, or newer if negative.
+    /// Scrolling past the newest entry follows new entries again.
+    pub fn scroll(&mut self, state: &TuiLoggerState, lines: isize) {
+        let ids: Vec<u64> = state.filtered(&self.filter).map(|e| e.id).collect();
+        let Some(last) = ids.len().checked_sub(1) else {
+            return;
+        };
+
+        let bottom = match self.anchor {
+            Some(anchor) => ids.iter().rposition(|id| *id <= anchor).unwrap_or(0),
+            None => last,
+        };
+
+        // Stop once the oldest entry is at the top of the pane
+        let top = self.height.saturating_sub(1).min(last);
+        let bottom = bottom.saturating_add_signed(-lines).clamp(top, last);
+        self.anchor = (bottom < last).then(|| ids[bottom]);
+    }
+
+    pub fn scroll_to_top(&mut self, state: &TuiLoggerState) {
+        self.scroll(state, isize::MAX);
+    }
+
+    pub fn follow(&mut self) {
+        self.anchor = None;
+    }
+
+    pub fn title(&self) -> String {
+        let mut title = "Log".to_string();
+
+        if self.filter.level < LevelFilter::Trace {
+            title += &format!(" [{} and up]", self.filter.level);
+        }
+        if !self.filter.search().is_empty() {
+            title += &format!(" [/{}]", self.filter.search());
+        }
+        if self.anchor.is_some() {
+            title += " [scrolled back, G to follow]";
+        }
+
+        title
+    }
+}
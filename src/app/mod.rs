@@ -1,5 +1,13 @@
+pub mod clock;
+pub mod editor;
 pub mod events;
+pub mod go;
+pub mod loader;
+pub mod log_pane;
+pub mod rehearsal;
+pub mod reload;
 pub mod render;
+pub mod rta;
 pub mod state;
 pub mod update;
 pub mod widgets;
@@ -0,0 +1,231 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use time::OffsetDateTime;
+
+use crate::prelude::*;
+use crate::util::fs::write_atomic;
+
+/// How long after the previous GO each GO happened, for every rehearsal run
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct TimingsFile {
+    runs: Vec<Run>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct Run {
+    #[serde(with = "time::serde::rfc3339")]
+    started: OffsetDateTime,
+    gos: Vec<Go>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct Go {
+    label: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    hint: String,
+    /// Time since the previous GO of the run, missing for the first
+    #[serde_as(as = "Option<serde_with::DurationSecondsWithFrac>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    after: Option<Duration>,
+}
+
+/// How the time before a cue's GO compares with earlier rehearsals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wait {
+    Unknown,
+    /// The usual time between the previous GO and this one
+    Expected(Duration),
+    /// Standing by for this cue, `elapsed` since the previous GO
+    Running {
+        expected: Duration,
+        elapsed: Duration,
+    },
+    /// Fired this run, after `actual`
+    Fired {
+        expected: Duration,
+        actual: Duration,
+    },
+}
+
+/// Rehearsal timings kept next to the script, recording new ones if this
+/// is a rehearsal
+pub struct Rehearsal {
+    path: PathBuf,
+    runs: Vec<Run>,
+    /// The run being recorded, if recording
+    current: Option<Run>,
+    /// Average wait before each cue over the earlier runs
+    expected: HashMap<String, Duration>,
+    /// Actual wait before each cue fired so far
+    fired: HashMap<String, Duration>,
+}
+
+impl Rehearsal {
+    /// Timings file for the script at `script`, like `show.timings.yaml` for `show.yaml`
+    pub fn path_for(script: &Path) -> PathBuf {
+        let stem = script.file_stem().unwrap_or_default().to_string_lossy();
+        script.with_file_name(format!("{stem}.timings.yaml"))
+    }
+
+    /// Load earlier runs from `path`, starting a new run if `record` is set.
+    /// Timings that can't be parsed are logged and left out, rather than
+    /// stopping the show
+    pub fn load(path: PathBuf, record: bool) -> Result<Self> {
+        let file: TimingsFile = match fs::read_to_string(&path) {
+            Ok(text) => serde_yaml::from_str(&text).unwrap_or_else(|e| {
+                log::error!(
+                    "Couldn't parse rehearsal timings in {}, starting without them: {e}",
+                    path.display()
+                );
+                TimingsFile::default()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => TimingsFile::default(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut waits: HashMap<&str, Vec<Duration>> = HashMap::new();
+        for run in &file.runs {
+            let mut seen = std::collections::HashSet::new();
+            for go in &run.gos {
+                // A cue gone back to in the same run doesn't say how long it usually takes
+                if let (Some(after), true) = (go.after, seen.insert(&go.label)) {
+                    waits.entry(&go.label).or_default().push(after);
+                }
+            }
+        }
+        let expected = waits
+            .into_iter()
+            .map(|(label, w)| {
+                (
+                    label.to_string(),
+                    w.iter().sum::<Duration>() / w.len() as u32,
+                )
+            })
+            .collect();
+
+        let current = record.then(|| Run {
+            started: OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc()),
+            gos: Vec::new(),
+        });
+
+        Ok(Self {
+            path,
+            runs: file.runs,
+            current,
+            expected,
+            fired: HashMap::new(),
+        })
+    }
+
+    pub fn recording(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Number of earlier runs
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Note a GO `after` the previous one, saving the run straight away if recording
+    pub fn record_go(&mut self, label: &str, hint: &str, after: Option<Duration>) {
+        if let Some(after) = after {
+            self.fired.entry(label.to_string()).or_insert(after);
+        }
+
+        let Some(current) = &mut self.current else {
+            return;
+        };
+
+        current.gos.push(Go {
+            label: label.to_string(),
+            hint: hint.to_string(),
+            after,
+        });
+
+        if let Err(e) = self.save() {
+            log::error!(
+                "Couldn't save rehearsal timings to {}: {e}",
+                self.path.display()
+            );
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let file = TimingsFile {
+            runs: self.runs.iter().chain(&self.current).cloned().collect(),
+        };
+        write_atomic(&self.path, serde_yaml::to_string(&file)?)?;
+        Ok(())
+    }
+
+    /// How the wait before `label` compares, given the time since the last GO
+    /// if it's the cue standing by
+    pub fn wait(&self, label: &str, standing_by: Option<Duration>) -> Wait {
+        let Some(&expected) = self.expected.get(label) else {
+            return Wait::Unknown;
+        };
+
+        match (self.fired.get(label), standing_by) {
+            (Some(&actual), _) => Wait::Fired { expected, actual },
+            (None, Some(elapsed)) => Wait::Running { expected, elapsed },
+            (None, None) => Wait::Expected(expected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, time::Duration};
+
+    use super::{Rehearsal, Wait};
+    use crate::util::fs::TempDir;
+
+    #[test]
+    fn averages_earlier_runs() {
+        let dir = TempDir::new("rehearsal");
+        let path = dir.join("show.timings.yaml");
+        let secs = Duration::from_secs;
+
+        for after in [10, 20] {
+            let mut rehearsal = Rehearsal::load(path.clone(), true).unwrap();
+            rehearsal.record_go("SQ1", "pg. 1", None);
+            rehearsal.record_go("SQ2", "pg. 3", Some(secs(after)));
+        }
+
+        let mut rehearsal = Rehearsal::load(path.clone(), false).unwrap();
+        rehearsal.record_go("SQ1", "pg. 1", None);
+        let saved = fs::read_to_string(&path).unwrap();
+
+        assert_eq!(rehearsal.runs(), 2);
+        assert!(saved.contains("hint: pg. 3"));
+        assert_eq!(rehearsal.wait("SQ1", None), Wait::Unknown);
+        assert_eq!(
+            rehearsal.wait("SQ2", Some(secs(20))),
+            Wait::Running {
+                expected: secs(15),
+                elapsed: secs(20)
+            }
+        );
+
+        rehearsal.record_go("SQ2", "pg. 3", Some(secs(12)));
+        assert_eq!(
+            rehearsal.wait("SQ2", None),
+            Wait::Fired {
+                expected: secs(15),
+                actual: secs(12)
+            }
+        );
+
+        fs::write(&path, "runs: [").unwrap();
+        let rehearsal = Rehearsal::load(path, false).unwrap();
+        assert_eq!(rehearsal.runs(), 0);
+    }
+}
@@ -0,0 +1,52 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Polls the modification time of a file, and the files it includes, to
+/// detect when any of them has been saved
+pub struct FileWatcher {
+    path: PathBuf,
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl FileWatcher {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let files = vec![(path.clone(), modified(&path))];
+
+        Self { path, files }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Watch `included` alongside the main file, replacing any previously included files
+    pub fn watch(&mut self, included: &[PathBuf]) {
+        self.files.truncate(1);
+        self.files
+            .extend(included.iter().map(|p| (p.clone(), modified(p))));
+    }
+
+    /// Whether any file has been modified since the last call
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+
+        for (path, last) in &mut self.files {
+            let modified = modified(path);
+
+            if modified.is_some() && modified != *last {
+                *last = modified;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -1,30 +1,52 @@
 use crate::{logging::LogWidget, prelude::*};
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, Paragraph, Widget},
+    widgets::{Block, Borders, Clear, Paragraph, TableState, Widget},
 };
 
-use super::{widgets::active_cues, AppState};
+use super::{
+    widgets::{
+        active_cues, clock, cue_form, cue_list, list_rows, output_meters, spectrum, standby,
+        ListRow,
+    },
+    AppState,
+};
 
 pub fn render<B: Backend>(term: &mut Terminal<B>, app: &mut AppState) -> Result<()> {
     term.draw(|f| {
+        let key_height = key_lines(current_keys(app), f.size().width).len() as u16 + 2;
         let [top, main, bottom, keys] = *Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Percentage(25),
                 Constraint::Min(10),
                 Constraint::Percentage(25),
-                Constraint::Length(3),
+                Constraint::Length(key_height),
             ].as_ref())
             .split(f.size())
         else {
             return;
         };
 
-        let [top_left, top_mid, top_right] = *Layout::default()
+        if app.log_pane().fullscreen() {
+            let [log_area, keys] = *Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length(key_height)].as_ref())
+                .split(f.size())
+            else {
+                return;
+            };
+
+            render_log(f, app, log_area);
+            f.render_widget(key_bar(app, keys.width), keys);
+            return;
+        }
+
+        let [output, standby_area, clock_area, active] = *Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
-                Constraint::Percentage(35),
+                Constraint::Percentage(25),
+                Constraint::Percentage(30),
                 Constraint::Length(30),
                 Constraint::Min(15),
             ].as_ref())
@@ -33,41 +55,278 @@ pub fn render<B: Backend>(term: &mut Terminal<B>, app: &mut AppState) -> Result<
             return;
         };
 
-        *app.list_height_mut() = main.height.checked_sub(2).unwrap_or(0);
-
-        f.render_stateful_widget(app.widget().clone(), main, app.list_state_mut());
-        f.render_widget(rta(), top_left);
-        f.render_widget(clock(), top_mid);
-        f.render_stateful_widget(active_cues(), top_right, app.engine_mut());
-        f.render_widget(hotkey_guide(), keys);
-        if let Ok(mut state) = app.logger_state().lock() {
-            f.render_stateful_widget(logger(), bottom, &mut state)
+        let statuses = (0..app.cuelist().len())
+            .map(|i| app.load_status(i))
+            .collect();
+        let mode = match (app.editor().active(), app.editor().dirty()) {
+            _ if app.editor().quitting() => Some("UNSAVED, q again to quit"),
+            (true, true) => Some("EDITING, unsaved"),
+            (true, false) => Some("EDITING"),
+            _ if app.rehearsal().recording() => Some("REHEARSAL"),
+            _ => None,
+        };
+        let rows = list_rows(app.script(), app.collapsed());
+        let cuelist = cue_list(
+            app.cuelist(),
+            &rows,
+            statuses,
+            app.waits(),
+            app.show_errors(),
+            app.load_progress(),
+            mode,
+        )
+        .highlight_style(highlight_style(app));
+        f.render_stateful_widget(cuelist, main, &mut list_view(app, &rows, main.height));
+        if let Some(form) = app.editor().form() {
+            let area = popup(main, 80, 9);
+            f.render_widget(Clear, area);
+            f.render_widget(cue_form(form), area);
         }
+        f.render_widget(standby_panel(app), standby_area);
+        let meter_height = app.engine_mut().meter().levels().len() as u16 + 2;
+        let [rta_area, meter_area] = *Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(meter_height)].as_ref())
+            .split(output)
+        else {
+            return;
+        };
+
+        // Account for the block border
+        let bars = rta_area.width.saturating_sub(2) as usize;
+        let rta = spectrum(app.rta_title()).levels(app.rta_levels(bars));
+
+        f.render_widget(rta, rta_area);
+        f.render_stateful_widget(output_meters(), meter_area, app.engine_mut());
+        f.render_stateful_widget(clock(), clock_area, app.clock_mut());
+        f.render_stateful_widget(active_cues(), active, app.engine_mut());
+        f.render_widget(key_bar(app, keys.width), keys);
+        render_log(f, app, bottom);
     })?;
 
     Ok(())
 }
 
-fn logger<'a>() -> LogWidget<'a> {
-    LogWidget::default().block(Block::default().borders(Borders::ALL).title("Log"))
+fn render_log<B: Backend>(f: &mut Frame<B>, app: &mut AppState, area: Rect) {
+    // Account for the block border
+    app.log_pane_mut()
+        .set_height(area.height.saturating_sub(2) as usize);
+
+    let pane = app.log_pane();
+    let border = if pane.focused() {
+        Style::new().fg(Color::Yellow)
+    } else {
+        Style::new()
+    };
+    // Only hold the lock long enough to copy out what fits in the pane
+    let entries = match app.logger_state().lock() {
+        Ok(state) => state.visible(pane.filter(), pane.anchor(), pane.height()),
+        Err(_) => Vec::new(),
+    };
+    let logger = LogWidget::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border)
+                .title(pane.title()),
+        )
+        .entries(entries);
+
+    f.render_widget(logger, area);
 }
 
-fn rta() -> impl Widget {
-    Paragraph::new("Placeholder")
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL).title("Output"))
+/// Whatever is being typed, or else the keys that can be pressed
+fn key_bar(app: &AppState, width: u16) -> Paragraph<'_> {
+    if let Some(input) = app.goto_prompt() {
+        return prompt(
+            "GOTO cue number: ",
+            input,
+            "[<Enter>] Go To | [<Esc>] Cancel",
+        );
+    }
+    if let Some(input) = app.levels_prompt() {
+        return prompt("Log levels: ", input, "[<Enter>] Apply | [<Esc>] Cancel");
+    }
+    if let Some(input) = app.log_pane().search_prompt() {
+        return prompt(
+            "Search log: ",
+            input,
+            "[<Enter>] Search | [<Esc>] Clear Search",
+        );
+    }
+
+    hotkey_guide(current_keys(app), width)
 }
 
-fn clock() -> impl Widget {
-    Paragraph::new("Placeholder")
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL).title("Clock"))
+fn current_keys(app: &AppState) -> Keys {
+    if app.editor().active() {
+        Keys::Editing
+    } else if app.log_pane().focused() {
+        Keys::Log
+    } else {
+        Keys::Normal
+    }
+}
+
+/// Table state that selects the row showing the selected cue, scrolled to
+/// keep it in the middle of the list
+fn list_view(app: &AppState, rows: &[ListRow], height: u16) -> TableState {
+    let row = app
+        .list_state()
+        .selected()
+        .and_then(|i| rows.iter().position(|r| r.shows(i)));
+
+    // Account for the block border and header
+    let offset = row.map_or(0, |r| {
+        r.saturating_sub(height.saturating_sub(3) as usize / 2)
+    });
+
+    TableState::default().with_selected(row).with_offset(offset)
+}
+
+fn highlight_style(app: &AppState) -> Style {
+    let go = app.go_guard();
+
+    if go.flashing() {
+        Style::new().bg(Color::Red)
+    } else if go.standby().is_some() && go.standby() == app.list_state().selected() {
+        Style::new().bg(Color::Yellow).fg(Color::Black)
+    } else {
+        Style::new().bg(Color::DarkGray)
+    }
 }
 
-fn hotkey_guide() -> impl Widget {
-    Paragraph::new(
-        "[q] Quit | [s]: Stop All | [j]: Select Next | [k]: Select Prev | [<Space>]: Run Selected",
+fn standby_panel(app: &AppState) -> impl Widget + '_ {
+    let Some(selected) = app.list_state().selected() else {
+        return standby();
+    };
+
+    let cuelist = app.cuelist();
+    let panel = standby().next(cuelist.get(selected + 1));
+
+    match cuelist.get(selected) {
+        Some(cue) => panel.cue(cue, app.load_status(selected)),
+        None => panel,
+    }
+}
+
+/// Centered area `percent_x` wide and `height` rows tall
+fn popup(area: Rect, percent_x: u16, height: u16) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = height.min(area.height);
+
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
     )
+}
+
+fn prompt<'a>(label: &'a str, input: &'a str, keys: &'a str) -> Paragraph<'a> {
+    Paragraph::new(Line::from(vec![
+        Span::from(label).bold(),
+        Span::from(input),
+        Span::from("▏").yellow(),
+        Span::from(format!("   {keys}")).dark_gray(),
+    ]))
     .alignment(Alignment::Center)
     .block(Block::default().borders(Borders::ALL))
 }
+
+#[derive(Clone, Copy)]
+enum Keys {
+    Normal,
+    Editing,
+    Log,
+}
+
+impl Keys {
+    fn guide(self) -> &'static [&'static str] {
+        match self {
+            Keys::Editing => &[
+                "[E/<Esc>] Leave Editor",
+                "[j/k] Select",
+                "[i] Insert",
+                "[<Enter>] Edit",
+                "[d] Delete",
+                "[J/K] Move Down/Up",
+                "[u] Undo",
+                "[n] Renumber",
+                "[o] Sort by Number",
+                "[w] Save",
+            ],
+            Keys::Log => &[
+                "[l/<Esc>] Leave Log",
+                "[L] Fullscreen",
+                "[j/k] Scroll",
+                "[PgUp/PgDn] Page",
+                "[g/G] Oldest/Follow",
+                "[f] Filter Level",
+                "[/] Search",
+                "[+/-] Log Level",
+                "[=] Set Levels",
+            ],
+            Keys::Normal => &[
+                "[q] Quit",
+                "[s]: Stop All",
+                "[j/k]: Select Next/Prev",
+                "[<Space>]: GO",
+                "[<Esc>]: Clear Standby",
+                "[a/A]: Start/Reset Act",
+                "[v]: RTA Source",
+                "[b]: RTA Bands",
+                "[g]: GOTO Number",
+                "[c]: Collapse Section",
+                "[ [/] ]: Prev/Next Section",
+                "[e]: Show Errors",
+                "[r]: Retry Load",
+                "[E]: Edit Cues",
+                "[l/L]: Log/Fullscreen",
+                "[+/-]: Log Level",
+            ],
+        }
+    }
+}
+
+/// The guide for `keys`, starting a new line wherever the next key wouldn't
+/// fit in a bar `width` wide
+fn key_lines(keys: Keys, width: u16) -> Vec<String> {
+    // Account for the block border
+    let width = width.saturating_sub(2) as usize;
+    let mut lines: Vec<String> = Vec::new();
+
+    for key in keys.guide() {
+        match lines.last_mut() {
+            Some(line) if line.len() + " | ".len() + key.len() <= width => {
+                *line += " | ";
+                *line += key;
+            }
+            _ => lines.push(key.to_string()),
+        }
+    }
+
+    lines
+}
+
+fn hotkey_guide(keys: Keys, width: u16) -> Paragraph<'static> {
+    let lines: Vec<Line> = key_lines(keys, width).into_iter().map(Line::from).collect();
+
+    Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{key_lines, Keys};
+
+    #[test]
+    fn key_guide_wraps_to_fit() {
+        let lines = key_lines(Keys::Normal, 80);
+
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| line.len() <= 78));
+        assert_eq!(lines.join(" | "), Keys::Normal.guide().join(" | "));
+    }
+}
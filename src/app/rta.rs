@@ -0,0 +1,58 @@
+use crate::sound::{
+    spectrum::{Resolution, SpectrumAnalyzer},
+    AudioEngine,
+};
+
+/// What the real-time analyzer is displaying and how
+#[derive(Default)]
+pub struct RtaView {
+    analyzer: SpectrumAnalyzer,
+    resolution: Resolution,
+    /// Show the cue selected in the cue list instead of the master output
+    follow_selected: bool,
+}
+
+impl RtaView {
+    pub fn toggle_resolution(&mut self) {
+        self.resolution = self.resolution.toggle();
+    }
+
+    /// Switch between the master output and the selected cue
+    pub fn toggle_source(&mut self) {
+        self.follow_selected = !self.follow_selected;
+        log::debug!(
+            "RTA showing {}",
+            if self.follow_selected {
+                "the selected cue"
+            } else {
+                "master"
+            }
+        );
+    }
+
+    /// `selected` is the label of the cue selected in the cue list
+    pub fn title(&self, engine: &AudioEngine, selected: Option<&str>) -> String {
+        let source = match (self.follow_selected, selected) {
+            (false, _) => "Master".to_string(),
+            (true, Some(label)) if engine.history(Some(label)).is_some() => label.to_string(),
+            (true, Some(label)) => format!("{label}, not playing"),
+            (true, None) => "no cue selected".to_string(),
+        };
+
+        format!("RTA: {source} ({})", self.resolution.name())
+    }
+
+    pub fn levels(&self, engine: &AudioEngine, selected: Option<&str>, bars: usize) -> Vec<f32> {
+        let source = match (self.follow_selected, selected) {
+            (false, _) => None,
+            (true, Some(label)) => Some(label),
+            (true, None) => return Vec::new(),
+        };
+
+        // Nothing to show while the selected cue isn't playing
+        match engine.history(source) {
+            Some(history) => self.analyzer.analyze(history, self.resolution, bars),
+            None => Vec::new(),
+        }
+    }
+}
@@ -1,111 +1,132 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashSet,
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
-use log::LevelFilter;
-use ratatui::widgets::{Table, TableState};
+use ratatui::widgets::TableState;
 
-use crate::logging::{TuiLogger, TuiLoggerState};
+use crate::logging::{self, LogHandle, LogLevels, TuiLoggerState};
 
-use crate::cues::{Cue, Script};
+use crate::cues::{Cue, CueNumber, Script, ScriptDiff};
 use crate::prelude::*;
-use crate::sound::{AudioEngine, ExecutableCue, ExecuteCueError};
-
-use super::widgets::cue_list;
-
-#[derive(Default)]
-enum LazyExecutable {
-    Ready(ExecutableCue),
-    Error,
-    #[default]
+use crate::sound::{cache, AudioEngine};
+use crate::util::format;
+
+use super::clock::ShowClock;
+use super::editor::{CueForm, Editor};
+use super::go::{GoAction, GoGuard};
+use super::loader::CueSlots;
+use super::log_pane::LogPane;
+use super::rehearsal::{Rehearsal, Wait};
+use super::reload::FileWatcher;
+use super::rta::RtaView;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadStatus {
+    Loaded,
+    Failed(String),
+    Loading,
     NotLoaded,
+    Running,
 }
 
-impl LazyExecutable {
-    fn take(&mut self) -> Option<ExecutableCue> {
-        if let Self::Error = self {
-            None
-        } else {
-            std::mem::replace(self, Self::NotLoaded).inner()
-        }
-    }
-
-    fn inner(self) -> Option<ExecutableCue> {
-        match self {
-            LazyExecutable::Ready(exe) => Some(exe),
-            _ => None,
-        }
-    }
-
-    fn load(&mut self, cue: &Cue) {
-        if matches!(self, Self::Ready(_)) {
-            return;
-        }
-
-        let label = cue.label();
-        match cue.action().prepare(Some(label)) {
-            Ok(exe) => {
-                log::debug!("Loaded cue `{label}`");
-                *self = LazyExecutable::Ready(exe);
-            }
-            Err(e) => {
-                log::error!("Error preparing cue `{label}`: {e}");
-                *self = LazyExecutable::Error;
-            }
-        }
-    }
-}
-
-pub struct AppState<'s> {
+pub struct AppState {
     active: bool,
-    widget: Table<'s>,
-    cuelist: &'s [Cue],
-    executables: Vec<LazyExecutable>,
+    script: Script,
+    watcher: FileWatcher,
+    executables: CueSlots,
     list_state: TableState,
-    logger_state: Arc<Mutex<TuiLoggerState>>,
+    logging: LogHandle,
+    log_pane: LogPane,
     engine: AudioEngine,
-    list_height: u16,
+    collapsed: HashSet<String>,
+    goto: Option<String>,
+    /// Log levels being typed, before they're applied
+    levels_input: Option<String>,
+    go: GoGuard,
+    clock: ShowClock,
+    rehearsal: Rehearsal,
+    rta: RtaView,
+    show_errors: bool,
+    editor: Editor,
 }
 
-impl<'a> AppState<'a> {
-    pub fn new(script: &'a Script) -> Result<Self> {
-        let logger_state = TuiLogger::init(LevelFilter::Debug)?;
-        log::info!("Logging initialized");
+impl AppState {
+    pub fn new(
+        script: Script,
+        path: impl AsRef<Path>,
+        log_levels: LogLevels,
+        rehearsal: bool,
+    ) -> Result<Self> {
+        let log_dir = script.logs().dir_for(path.as_ref());
+        let logging = logging::init(log_levels, &log_dir, script.logs().keep())?;
+        log::info!("Logging initialized at {}", logging.levels());
+
+        let clock = ShowClock::new(script.schedule().clone());
+
+        let rehearsal = Rehearsal::load(Rehearsal::path_for(path.as_ref()), rehearsal)?;
+        if rehearsal.recording() {
+            log::info!(
+                "Recording rehearsal timings, {} earlier runs",
+                rehearsal.runs()
+            );
+        }
 
         let engine = AudioEngine::try_init_default()?;
         log::info!("Audio engine initialized");
 
-        let cuelist = script.cuelist();
+        cache::set_budget(script.cache().budget());
 
-        let executables = cuelist
-            .iter()
-            .map(|cue| {
-                let mut loader = LazyExecutable::default();
-                loader.load(cue);
-                loader
-            })
-            .collect();
-        log::info!("Finished loading cues");
+        let mut watcher = FileWatcher::new(path);
+        watcher.watch(script.files());
+
+        let executables = CueSlots::new(&script, 0);
+        log::info!(
+            "Loading the first {} cues in the background",
+            executables.progress().1
+        );
 
         Ok(Self {
             active: true,
-            widget: cue_list(script.cuelist()),
-            cuelist,
+            go: GoGuard::new(script.go().clone()),
+            script,
+            watcher,
             executables,
             list_state: TableState::default().with_selected(Some(0)),
-            logger_state,
+            logging,
+            log_pane: LogPane::default(),
             engine,
-            list_height: 0,
+            collapsed: HashSet::new(),
+            goto: None,
+            levels_input: None,
+            clock,
+            rehearsal,
+            rta: RtaView::default(),
+            show_errors: false,
+            editor: Editor::default(),
         })
     }
 
     pub fn quit(&mut self) -> Result<()> {
+        if !self.editor.confirm_quit() {
+            log::warn!("There are unsaved edits to the script. Press q again to quit without saving them, or w in edit mode to save.");
+            return Ok(());
+        }
+
+        if self.editor.dirty() {
+            log::warn!("Quitting with unsaved changes to the script");
+        }
+
         log::info!("Quitting application");
+        log::logger().flush();
         self.active = false;
 
         Ok(())
     }
 }
 
-impl<'a> AppState<'a> {
+impl AppState {
     pub fn active(&self) -> bool {
         self.active
     }
@@ -119,76 +140,371 @@ impl<'a> AppState<'a> {
     }
 
     pub fn logger_state(&self) -> &Arc<Mutex<TuiLoggerState>> {
-        &self.logger_state
+        self.logging.history()
+    }
+
+    pub fn log_levels(&self) -> LogLevels {
+        self.logging.levels()
+    }
+
+    pub fn set_log_levels(&mut self, levels: LogLevels) {
+        self.logging.set_levels(levels);
+        log::info!("Log level set to {}", self.logging.levels());
+    }
+
+    /// Log more detail, or less if `up` is false
+    pub fn step_log_level(&mut self, up: bool) {
+        let mut levels = self.logging.levels();
+        if up {
+            levels.raise();
+        } else {
+            levels.lower();
+        }
+        self.set_log_levels(levels);
+    }
+
+    /// The log levels typed so far, if they're being changed
+    pub fn levels_prompt(&self) -> Option<&str> {
+        self.levels_input.as_deref()
+    }
+
+    pub fn start_levels(&mut self) {
+        self.levels_input = Some(self.log_levels().to_string());
+    }
+
+    pub fn cancel_levels(&mut self) {
+        self.levels_input = None;
+    }
+
+    pub fn levels_input(&mut self) -> Option<&mut String> {
+        self.levels_input.as_mut()
+    }
+
+    pub fn submit_levels(&mut self) {
+        let Some(input) = self.levels_input.take() else {
+            return;
+        };
+
+        match input.parse::<LogLevels>() {
+            Ok(levels) => self.set_log_levels(levels),
+            Err(e) => log::error!("{e}"),
+        }
+    }
+
+    pub fn log_pane(&self) -> &LogPane {
+        &self.log_pane
+    }
+
+    pub fn log_pane_mut(&mut self) -> &mut LogPane {
+        &mut self.log_pane
+    }
+
+    /// Scroll the log pane `lines` back, or forward if negative
+    pub fn scroll_log(&mut self, lines: isize) {
+        if let Ok(state) = self.logging.history().lock() {
+            self.log_pane.scroll(&state, lines);
+        }
     }
 
-    pub fn widget(&self) -> &Table<'a> {
-        &self.widget
+    pub fn scroll_log_to_top(&mut self) {
+        if let Ok(state) = self.logging.history().lock() {
+            self.log_pane.scroll_to_top(&state);
+        }
+    }
+
+    pub fn collapsed(&self) -> &HashSet<String> {
+        &self.collapsed
     }
 
-    pub fn list_height_mut(&mut self) -> &mut u16 {
-        &mut self.list_height
+    pub fn script(&self) -> &Script {
+        &self.script
     }
 
     pub fn engine_mut(&mut self) -> &mut AudioEngine {
         &mut self.engine
     }
+
+    pub fn cuelist(&self) -> &[Cue] {
+        self.script.cuelist()
+    }
+
+    pub fn load_status(&self, idx: usize) -> LoadStatus {
+        let running = self
+            .cuelist()
+            .get(idx)
+            .is_some_and(|c| self.engine.get_sink(c.label()).is_some());
+
+        if running {
+            return LoadStatus::Running;
+        }
+
+        self.executables.status(idx)
+    }
+
+    /// Number of cues around the selection that have finished loading
+    /// (successfully or not), and the number kept loaded
+    pub fn load_progress(&self) -> (usize, usize) {
+        self.executables.progress()
+    }
+
+    pub fn show_errors(&self) -> bool {
+        self.show_errors
+    }
+
+    pub fn clock_mut(&mut self) -> &mut ShowClock {
+        &mut self.clock
+    }
+
+    pub fn go_guard(&self) -> &GoGuard {
+        &self.go
+    }
+
+    pub fn rehearsal(&self) -> &Rehearsal {
+        &self.rehearsal
+    }
+
+    /// How the wait before each cue compares with earlier rehearsals, counting
+    /// the time since the last GO for the selected cue
+    pub fn waits(&self) -> Vec<Wait> {
+        let selected = self.list_state.selected();
+        let since_last = self.clock.since_last_go();
+
+        self.cuelist()
+            .iter()
+            .enumerate()
+            .map(|(i, cue)| {
+                let standing_by = since_last.filter(|_| selected == Some(i));
+                self.rehearsal.wait(cue.label(), standing_by)
+            })
+            .collect()
+    }
+
+    pub fn editor(&self) -> &Editor {
+        &self.editor
+    }
 }
 
-impl AppState<'_> {
+impl AppState {
     pub fn select_next(&mut self) -> Result<()> {
+        if self.script.cuelist().is_empty() {
+            return Ok(());
+        }
+
         let i = match self.list_state_mut().selected() {
-            Some(t) if t < self.cuelist.len() - 1 => t + 1,
+            Some(t) if t < self.script.cuelist().len() - 1 => t + 1,
 
             _ => 0,
         };
 
         self.list_state_mut().select(Some(i));
-        self.recenter();
+        self.executables.focus(&self.script, i);
 
         Ok(())
     }
 
     pub fn select_prev(&mut self) -> Result<()> {
+        if self.script.cuelist().is_empty() {
+            return Ok(());
+        }
+
         let i = match self.list_state_mut().selected() {
             Some(t) if t > 0 => t - 1,
 
-            _ => self.cuelist.len() - 1,
+            _ => self.script.cuelist().len() - 1,
         };
 
         self.list_state_mut().select(Some(i));
-        self.recenter();
+        self.executables.focus(&self.script, i);
 
         Ok(())
     }
 
-    fn recenter(&mut self) {
-        let Some(selected) = self.list_state().selected() else {
-            return
+    /// The cue number typed so far, if a GOTO is being entered
+    pub fn goto_prompt(&self) -> Option<&str> {
+        self.goto.as_deref()
+    }
+
+    pub fn start_goto(&mut self) {
+        self.goto = Some(String::new());
+    }
+
+    pub fn cancel_goto(&mut self) {
+        self.goto = None;
+    }
+
+    pub fn goto_input(&mut self) -> Option<&mut String> {
+        self.goto.as_mut()
+    }
+
+    /// Select the cue with the number that was typed in
+    pub fn submit_goto(&mut self) {
+        let Some(input) = self.goto.take() else {
+            return;
         };
 
-        let list_height = self.list_height;
-        let offset = self.list_state_mut().offset_mut();
-        *offset = selected.saturating_sub(list_height as usize / 2);
+        let number = match input.parse::<CueNumber>() {
+            Ok(n) => n,
+            Err(e) => {
+                log::warn!("{e}");
+                return;
+            }
+        };
+
+        match self
+            .cuelist()
+            .iter()
+            .position(|c| c.number() == Some(&number))
+        {
+            Some(i) => {
+                log::info!("Jumped to cue {number}");
+                self.select(i);
+            }
+            None => log::warn!("There is no cue numbered {number}"),
+        }
     }
 
-    pub fn execute_selected(&mut self) -> Result<(), ExecuteCueError> {
-        let Some((loader, cue)) = self.list_state().selected()
-            .and_then(|idx| self.executables.get_mut(idx).zip(self.cuelist.get(idx)))
+    /// Collapse or expand the section the selected cue is in
+    pub fn toggle_section(&mut self) {
+        let Some(section) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.script.section_of(i))
         else {
-            return Err(ExecuteCueError::General("cue index out of bounds"))
+            return
         };
 
-        loader.load(cue);
+        let name = section.name().to_string();
+        if !self.collapsed.remove(&name) {
+            self.collapsed.insert(name);
+        }
+    }
 
-        if let Some(exe) = loader.take() {
-            log::info!("Executing cue {}", cue.label());
-            exe.execute(&mut self.engine)
-        } else {
-            Err(ExecuteCueError::General("Cue failed to load"))
+    /// Select the first cue of the next section
+    pub fn next_section(&mut self) {
+        let selected = self.list_state.selected().unwrap_or(0);
+        let next = self
+            .script
+            .section_starts()
+            .into_iter()
+            .map(|(i, _)| i)
+            .find(|i| *i > selected);
+
+        if let Some(i) = next {
+            self.select(i);
+        }
+    }
+
+    /// Select the first cue of the current section, or of the previous
+    /// section if that's already selected
+    pub fn prev_section(&mut self) {
+        let selected = self.list_state.selected().unwrap_or(0);
+        let prev = self
+            .script
+            .section_starts()
+            .into_iter()
+            .map(|(i, _)| i)
+            .rfind(|i| *i < selected);
+
+        if let Some(i) = prev {
+            self.select(i);
         }
     }
 
+    pub fn go(&mut self) -> Result<()> {
+        let Some(selected) = self.list_state().selected() else {
+            return Ok(());
+        };
+
+        let cue = self.script.cuelist().get(selected);
+        let (label, hint) = cue.map_or(("", ""), |c| (c.label(), c.hint()));
+
+        match self.go.press(selected) {
+            GoAction::Fire => {
+                // Never load on GO; a cue that isn't ready stays selected so
+                // the operator can try again
+                let exe = match self.executables.take(&self.script, selected) {
+                    Ok(exe) => exe,
+                    Err(e) => {
+                        self.go.reject(selected);
+                        log::error!("Couldn't fire cue {label}, {e}");
+                        return Ok(());
+                    }
+                };
+
+                self.rehearsal
+                    .record_go(label, hint, self.clock.since_last_go());
+                self.clock.record_go();
+                let elapsed = format::hh_mm_ss(self.clock.show_elapsed().unwrap_or_default());
+                if let Some(cue) = cue {
+                    log::info!(
+                        "Executing cue {label} at {elapsed} show time: {}",
+                        cue.action()
+                    );
+                }
+                let result = exe.execute(&mut self.engine);
+                if let Err(e) = &result {
+                    log::error!("Error executing cue {label}: {e}");
+                }
+                logging::log_go(label, result.is_err());
+                self.select_next()
+            }
+            GoAction::Standby => {
+                log::info!("Standing by for cue {label}");
+                Ok(())
+            }
+            GoAction::Ignored => {
+                log::warn!("Ignored GO for cue {label} during lockout");
+                Ok(())
+            }
+        }
+    }
+
+    pub fn start_act(&mut self) {
+        self.clock.start_act()
+    }
+
+    pub fn reset_act(&mut self) {
+        self.clock.reset_act()
+    }
+
+    /// Label of the cue selected in the cue list
+    fn selected_label(&self) -> Option<&str> {
+        let selected = self.list_state.selected()?;
+        self.cuelist().get(selected).map(Cue::label)
+    }
+
+    pub fn rta_title(&self) -> String {
+        self.rta.title(&self.engine, self.selected_label())
+    }
+
+    pub fn rta_levels(&self, bars: usize) -> Vec<f32> {
+        self.rta.levels(&self.engine, self.selected_label(), bars)
+    }
+
+    pub fn toggle_rta_resolution(&mut self) {
+        self.rta.toggle_resolution()
+    }
+
+    pub fn toggle_rta_source(&mut self) {
+        self.rta.toggle_source()
+    }
+
+    pub fn toggle_errors(&mut self) {
+        self.show_errors = !self.show_errors
+    }
+
+    pub fn retry_selected(&mut self) {
+        let Some(idx) = self.list_state.selected() else {
+            return;
+        };
+
+        self.executables.request(&self.script, idx);
+    }
+
+    pub fn clear_standby(&mut self) {
+        self.go.clear_standby()
+    }
+
     pub fn stop_all(&mut self) {
         log::info!("Stopping all active cues");
         self.engine.stop_all()
@@ -196,5 +512,239 @@ impl AppState<'_> {
 
     pub fn upkeep(&mut self) {
         self.engine.gc();
+
+        self.executables.finish_loads();
+
+        if self.watcher.changed() {
+            self.reload_script();
+        }
+    }
+
+    fn reload_script(&mut self) {
+        let path = self.watcher.path();
+        log::info!("{} changed, reloading", path.display());
+
+        match Script::load_from(path).and_then(Script::validate) {
+            Ok(script) => {
+                if self.editor.dirty() {
+                    log::warn!("Unsaved edits were replaced by the changes on disk");
+                }
+                self.editor.reset();
+
+                let diff = self.apply_script(script);
+                log::info!("Script reloaded: {diff}");
+            }
+            Err(e) => log::error!("Keeping previous script, couldn't reload: {e}"),
+        }
+    }
+
+    /// Swap in a new version of the script, keeping loaded executables for
+    /// cues that haven't changed. Running cues are left alone.
+    pub fn apply_script(&mut self, script: Script) -> ScriptDiff {
+        let diff = self.script.diff(&script);
+
+        let selected_label = self
+            .list_state
+            .selected()
+            .and_then(|i| self.cuelist().get(i))
+            .map(|c| c.label().to_string());
+
+        let old = std::mem::replace(&mut self.script, script);
+        self.watcher.watch(self.script.files());
+        self.go.set_settings(self.script.go().clone());
+        self.clock.set_schedule(self.script.schedule().clone());
+        cache::set_budget(self.script.cache().budget());
+
+        let len = self.cuelist().len();
+        let selected = selected_label
+            .and_then(|l| self.cuelist().iter().position(|c| c.label() == l))
+            .or(self
+                .list_state
+                .selected()
+                .map(|i| i.min(len.saturating_sub(1))))
+            .filter(|_| len > 0);
+
+        self.list_state.select(selected);
+        self.executables
+            .replace(&old, &self.script, selected.unwrap_or_default());
+
+        diff
+    }
+}
+
+impl AppState {
+    pub fn toggle_edit(&mut self) {
+        self.editor.toggle();
+        log::info!(
+            "{} edit mode",
+            if self.editor.active() {
+                "Entered"
+            } else {
+                "Left"
+            }
+        );
+    }
+
+    pub fn insert_cue(&mut self) {
+        let cues = self.cuelist();
+        let index = self
+            .list_state
+            .selected()
+            .map_or(0, |i| i + 1)
+            .min(cues.len());
+
+        // Suggest a number between the neighbouring cues if the show uses numbers
+        let number = cues.iter().any(|c| c.number().is_some()).then(|| {
+            let before = cues[..index].iter().rev().find_map(Cue::number);
+            let after = cues[index..].iter().find_map(Cue::number);
+            CueNumber::between(before, after)
+        });
+
+        self.editor.open(CueForm::insert(index, number.flatten()));
+    }
+
+    pub fn edit_selected(&mut self) {
+        let Some((i, cue)) = self
+            .list_state
+            .selected()
+            .and_then(|i| Some((i, self.cuelist().get(i)?)))
+        else {
+            return;
+        };
+
+        match self.script.expander() {
+            Ok(expander) => self.editor.open(CueForm::edit(i, cue, &expander)),
+            Err(e) => log::error!("Can't edit cue {}: {e}", cue.label()),
+        }
+    }
+
+    pub fn form_mut(&mut self) -> Option<&mut CueForm> {
+        self.editor.form_mut()
+    }
+
+    /// Re-check the open form after it's been changed
+    pub fn check_form(&mut self) {
+        if let Some(form) = self.editor.form_mut() {
+            form.check(&self.script);
+        }
+    }
+
+    pub fn cancel_quit(&mut self) {
+        self.editor.cancel_quit()
+    }
+
+    pub fn cancel_form(&mut self) {
+        self.editor.close();
+    }
+
+    /// Apply the open form if it's valid, otherwise leave it open
+    pub fn submit_form(&mut self) {
+        let Some(form) = self.editor.form_mut() else {
+            return;
+        };
+        let Some(script) = form.submit(&self.script) else {
+            return;
+        };
+        let index = form.index();
+
+        self.editor.close();
+        self.edit_script(script);
+        self.select(index);
+    }
+
+    pub fn delete_selected(&mut self) {
+        let Some(i) = self
+            .list_state
+            .selected()
+            .filter(|i| *i < self.cuelist().len())
+        else {
+            return;
+        };
+
+        let mut cues = self.cuelist().to_vec();
+        cues.remove(i);
+        self.try_edit(cues);
+    }
+
+    /// Swap the selected cue with its neighbour, `up` or down the list
+    pub fn move_selected(&mut self, up: bool) {
+        let Some(i) = self.list_state.selected() else {
+            return;
+        };
+        let j = if up { i.checked_sub(1) } else { Some(i + 1) };
+        let Some(j) = j.filter(|j| *j < self.cuelist().len()) else {
+            return;
+        };
+
+        let mut cues = self.cuelist().to_vec();
+        cues.swap(i, j);
+        self.try_edit(cues);
+    }
+
+    pub fn renumber_cues(&mut self) {
+        let script = self.script.clone().renumber(1, 1);
+        self.try_edit(script.cuelist().to_vec());
+    }
+
+    pub fn sort_cues(&mut self) {
+        let script = self.script.clone().sort_by_number();
+        self.try_edit(script.cuelist().to_vec());
+    }
+
+    pub fn undo_edit(&mut self) {
+        let Some(result) = self.editor.undo(&self.script) else {
+            log::info!("Nothing to undo");
+            return;
+        };
+
+        match result {
+            Ok(script) => {
+                let diff = self.apply_script(script);
+                log::info!("Undid edit: {diff}");
+            }
+            Err(e) => log::error!("Couldn't undo edit: {e}"),
+        }
+    }
+
+    pub fn save_script(&mut self) {
+        let path = self.watcher.path().to_path_buf();
+
+        match self.script.save_to(&path) {
+            Ok(()) => {
+                // Don't reload what was just written
+                self.watcher.changed();
+                self.editor.saved();
+                log::info!("Saved script to {}", path.display());
+            }
+            Err(e) => log::error!("Couldn't save script to {}: {e}", path.display()),
+        }
+    }
+
+    fn try_edit(&mut self, cues: Vec<Cue>) {
+        match self
+            .script
+            .clone()
+            .with_cuelist(cues)
+            .validate_without_files()
+        {
+            Ok(script) => self.edit_script(script),
+            Err(e) => log::error!("Can't make that edit: {e}"),
+        }
+    }
+
+    fn edit_script(&mut self, script: Script) {
+        self.editor.record(self.script.cuelist());
+        let diff = self.apply_script(script);
+        log::info!("Edited script: {diff}");
+    }
+
+    fn select(&mut self, index: usize) {
+        let Some(last) = self.cuelist().len().checked_sub(1) else {
+            return;
+        };
+        let index = index.min(last);
+
+        self.list_state.select(Some(index));
+        self.executables.focus(&self.script, index);
     }
 }
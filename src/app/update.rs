@@ -1,7 +1,7 @@
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{prelude::Backend, Terminal};
 
-use super::{events::Event, render::render, AppState};
+use super::{events::Event, log_pane::LogPane, render::render, AppState};
 
 use crate::prelude::*;
 
@@ -17,16 +17,248 @@ pub fn update<B: Backend>(event: Event, term: &mut Terminal<B>, app: &mut AppSta
 }
 
 pub fn handle_key(key: KeyEvent, app: &mut AppState) -> Result<()> {
+    // Quitting with unsaved edits needs two presses of q in a row
+    if key.code != KeyCode::Char('q') {
+        app.cancel_quit();
+    }
+
+    if app.editor().form().is_some() {
+        handle_form_key(key, app);
+        return Ok(());
+    }
+
+    if app.goto_prompt().is_some() {
+        handle_goto_key(key, app);
+        return Ok(());
+    }
+
+    if app.levels_prompt().is_some() {
+        handle_levels_key(key, app);
+        return Ok(());
+    }
+
+    if app.log_pane_mut().search_input().is_some() {
+        handle_log_search_key(key, app.log_pane_mut());
+        return Ok(());
+    }
+
+    if app.editor().active() {
+        return handle_edit_key(key, app);
+    }
+
+    // Anything the log pane doesn't use still works, so GO is never blocked
+    if app.log_pane().focused() && handle_log_key(key, app) {
+        return Ok(());
+    }
+
     match key.code {
         KeyCode::Char('q') => app.quit(),
         KeyCode::Down | KeyCode::Char('j') => app.select_next(),
         KeyCode::Up | KeyCode::Char('k') => app.select_prev(),
-        KeyCode::Char(' ') => {
-            app.execute_selected()
-                .unwrap_or_else(|e| log::error!("Error executing cue: {e}"));
-            app.select_next()
+        KeyCode::Char(' ') => app.go(),
+        KeyCode::Char('a') => {
+            app.start_act();
+            Ok(())
+        }
+        KeyCode::Char('A') => {
+            app.reset_act();
+            Ok(())
+        }
+        KeyCode::Char('v') => {
+            app.toggle_rta_source();
+            Ok(())
+        }
+        KeyCode::Char('b') => {
+            app.toggle_rta_resolution();
+            Ok(())
+        }
+        KeyCode::Char('e') => {
+            app.toggle_errors();
+            Ok(())
+        }
+        KeyCode::Char('r') => {
+            app.retry_selected();
+            Ok(())
+        }
+        KeyCode::Esc => {
+            app.clear_standby();
+            Ok(())
+        }
+        KeyCode::Char('s') => {
+            app.stop_all();
+            Ok(())
+        }
+        KeyCode::Char('E') => {
+            app.toggle_edit();
+            Ok(())
+        }
+        KeyCode::Char('g') => {
+            app.start_goto();
+            Ok(())
+        }
+        KeyCode::Char('c') => {
+            app.toggle_section();
+            Ok(())
+        }
+        KeyCode::Char('[') => {
+            app.prev_section();
+            Ok(())
+        }
+        KeyCode::Char(']') => {
+            app.next_section();
+            Ok(())
+        }
+        KeyCode::Char('l') => {
+            app.log_pane_mut().toggle_focus();
+            Ok(())
+        }
+        KeyCode::Char('L') => {
+            app.log_pane_mut().toggle_fullscreen();
+            Ok(())
+        }
+        KeyCode::Char('+') => {
+            app.step_log_level(true);
+            Ok(())
+        }
+        KeyCode::Char('-') => {
+            app.step_log_level(false);
+            Ok(())
         }
-        KeyCode::Char('s') => Ok(app.stop_all()),
         _ => Ok(()),
     }
 }
+
+/// Returns whether the key was used by the log pane
+fn handle_log_key(key: KeyEvent, app: &mut AppState) -> bool {
+    let page = app.log_pane().height().max(1) as isize;
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('l') => app.log_pane_mut().toggle_focus(),
+        KeyCode::Char('L') => app.log_pane_mut().toggle_fullscreen(),
+        KeyCode::Up | KeyCode::Char('k') => app.scroll_log(1),
+        KeyCode::Down | KeyCode::Char('j') => app.scroll_log(-1),
+        KeyCode::PageUp => app.scroll_log(page),
+        KeyCode::PageDown => app.scroll_log(-page),
+        KeyCode::Home | KeyCode::Char('g') => app.scroll_log_to_top(),
+        KeyCode::End | KeyCode::Char('G') => app.log_pane_mut().follow(),
+        KeyCode::Char('f') => app.log_pane_mut().cycle_level(),
+        KeyCode::Char('/') => app.log_pane_mut().start_search(),
+        KeyCode::Char('=') => app.start_levels(),
+        _ => return false,
+    }
+
+    true
+}
+
+/// Every character goes into the search, like the other prompts
+fn handle_log_search_key(key: KeyEvent, pane: &mut LogPane) {
+    match key.code {
+        KeyCode::Esc => pane.cancel_search(),
+        KeyCode::Enter => pane.submit_search(),
+        KeyCode::Backspace => {
+            pane.search_input().map(String::pop);
+        }
+        KeyCode::Char(c) => {
+            if let Some(input) = pane.search_input() {
+                input.push(c)
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_edit_key(key: KeyEvent, app: &mut AppState) -> Result<()> {
+    match key.code {
+        KeyCode::Char('q') => return app.quit(),
+        KeyCode::Down | KeyCode::Char('j') => return app.select_next(),
+        KeyCode::Up | KeyCode::Char('k') => return app.select_prev(),
+        KeyCode::Esc | KeyCode::Char('E') => app.toggle_edit(),
+        KeyCode::Char('i') => app.insert_cue(),
+        KeyCode::Enter => app.edit_selected(),
+        KeyCode::Char('d') => app.delete_selected(),
+        KeyCode::Char('J') => app.move_selected(false),
+        KeyCode::Char('K') => app.move_selected(true),
+        KeyCode::Char('u') => app.undo_edit(),
+        KeyCode::Char('n') => app.renumber_cues(),
+        KeyCode::Char('o') => app.sort_cues(),
+        KeyCode::Char('w') => app.save_script(),
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn handle_form_key(key: KeyEvent, app: &mut AppState) {
+    match key.code {
+        KeyCode::Esc => return app.cancel_form(),
+        KeyCode::Enter => return app.submit_form(),
+        _ => {}
+    }
+
+    let Some(form) = app.form_mut() else { return };
+
+    match key.code {
+        KeyCode::Tab | KeyCode::Down => form.next_field(),
+        KeyCode::BackTab | KeyCode::Up => form.prev_field(),
+        KeyCode::Backspace => form.pop(),
+        KeyCode::Char(c) => form.push(c),
+        _ => return,
+    }
+
+    app.check_form();
+}
+
+fn handle_levels_key(key: KeyEvent, app: &mut AppState) {
+    match key.code {
+        KeyCode::Esc => app.cancel_levels(),
+        KeyCode::Enter => app.submit_levels(),
+        KeyCode::Backspace => {
+            app.levels_input().map(String::pop);
+        }
+        KeyCode::Char(c) => {
+            if let Some(input) = app.levels_input() {
+                input.push(c)
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_goto_key(key: KeyEvent, app: &mut AppState) {
+    match key.code {
+        KeyCode::Esc => app.cancel_goto(),
+        KeyCode::Enter => app.submit_goto(),
+        KeyCode::Backspace => {
+            app.goto_input().map(String::pop);
+        }
+        KeyCode::Char(c) if c.is_ascii_digit() || c == '.' => {
+            if let Some(input) = app.goto_input() {
+                input.push(c)
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    use super::handle_log_search_key;
+    use crate::app::log_pane::LogPane;
+
+    #[test]
+    fn log_search_takes_every_character() {
+        let mut pane = LogPane::default();
+        pane.start_search();
+
+        for c in "sq 1".chars() {
+            handle_log_search_key(KeyEvent::from(KeyCode::Char(c)), &mut pane);
+        }
+        assert_eq!(pane.search_prompt(), Some("sq 1"));
+
+        handle_log_search_key(KeyEvent::from(KeyCode::Enter), &mut pane);
+        assert_eq!(pane.search_prompt(), None);
+        assert_eq!(pane.filter().search(), "sq 1");
+    }
+}
@@ -1,7 +1,16 @@
+use std::time::Duration;
+
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, BorderType, Borders, Row, StatefulWidget, Table, Widget};
+use ratatui::widgets::{Block, BorderType, Borders, LineGauge, StatefulWidget, Widget};
+
+use crate::sound::{metadata::PlaybackMeta, AudioEngine};
+use crate::util::format;
+
+/// Remaining time below which a cue's progress bar turns amber
+const WARN_REMAINING: Duration = Duration::from_secs(15);
 
-use crate::sound::AudioEngine;
+/// Remaining time below which a cue's progress bar turns red
+const CRITICAL_REMAINING: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Default)]
 pub struct ActiveCueList<'b> {
@@ -15,38 +24,93 @@ impl<'b> ActiveCueList<'b> {
     }
 }
 
+fn progress_color(meta: &PlaybackMeta) -> Color {
+    let remaining = meta.remaining();
+
+    if meta.repeat {
+        Color::Blue
+    } else if remaining < CRITICAL_REMAINING {
+        Color::Red
+    } else if remaining < WARN_REMAINING {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
+
+fn status_line<'a>(label: &'a str, volume: f32, meta: &PlaybackMeta) -> Line<'a> {
+    let mut spans = vec![
+        Span::from(label).bold(),
+        Span::from(format!(" {:>3.0}%", volume * 100.0)),
+    ];
+
+    if meta.is_fading() {
+        spans.push(Span::from(" fading").yellow());
+    }
+    if meta.repeat {
+        spans.push(Span::from(format!(" loop {}", meta.loops + 1)).blue());
+    }
+    if let Some(track) = &meta.track {
+        spans.push(Span::from(format!(" {track}")).italic());
+    }
+
+    Line::from(spans)
+}
+
 impl StatefulWidget for ActiveCueList<'_> {
     type State = AudioEngine;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        let mut list = Table::new(
-            state
-                .metadata()
-                .map(|(l, m)| {
-                    let meta = m.lock().unwrap();
-
-                    let elapsed = meta.start.elapsed();
-                    let total = meta.duration;
-
-                    let timestamp = format!(
-                        "{:02}:{:02}/{:02}:{:02}",
-                        elapsed.as_secs().saturating_div(60),
-                        elapsed.as_secs().wrapping_rem(60),
-                        total.as_secs().saturating_div(60),
-                        total.as_secs().wrapping_rem(60)
-                    );
-
-                    Row::new(vec![l.to_string(), timestamp])
-                })
-                .collect::<Vec<_>>(),
-        )
-        .widths(&[Constraint::Min(10), Constraint::Length(11)]);
-
-        if let Some(b) = self.block {
-            list = list.block(b)
-        }
+        let area = match self.block {
+            Some(b) => {
+                let inner = b.inner(area);
+                b.render(area, buf);
+                inner
+            }
+            None => area,
+        };
+
+        for (i, (label, volume, meta)) in state.active().into_iter().enumerate() {
+            let y = area.top() + 2 * i as u16;
+            if y + 1 >= area.bottom() {
+                break;
+            }
+
+            let Ok(meta) = meta.lock() else {
+                continue;
+            };
+
+            buf.set_line(
+                area.left(),
+                y,
+                &status_line(label, volume, &meta),
+                area.width,
+            );
+
+            let ratio = if meta.duration.is_zero() {
+                0.0
+            } else {
+                meta.elapsed().as_secs_f64() / meta.duration.as_secs_f64()
+            };
+
+            let gauge = LineGauge::default()
+                .ratio(ratio.clamp(0.0, 1.0))
+                .label(format!(
+                    "{} -{} ",
+                    format::mm_ss(meta.elapsed()),
+                    format::mm_ss(meta.remaining())
+                ))
+                .gauge_style(Style::new().fg(progress_color(&meta)));
 
-        Widget::render(list, area, buf)
+            gauge.render(
+                Rect {
+                    y: y + 1,
+                    height: 1,
+                    ..area
+                },
+                buf,
+            );
+        }
     }
 }
 
@@ -58,3 +122,68 @@ pub fn active_cues() -> ActiveCueList<'static> {
             .title("Active Cues"),
     )
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use ratatui::style::Color;
+
+    use super::{progress_color, status_line};
+    use crate::sound::metadata::PlaybackMeta;
+
+    /// A minute long cue that started `elapsed` seconds ago
+    fn playing(elapsed: u64) -> PlaybackMeta {
+        let mut meta = PlaybackMeta::new(Duration::from_secs(60));
+        meta.start = Instant::now() - Duration::from_secs(elapsed);
+        meta
+    }
+
+    fn text(meta: &PlaybackMeta) -> String {
+        status_line("SQ1", 0.8, meta)
+            .spans
+            .iter()
+            .map(|s| s.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn remaining_time_colours() {
+        let meta = playing(10);
+        assert_eq!(meta.remaining().as_secs(), 49);
+        assert_eq!(progress_color(&meta), Color::Green);
+
+        assert_eq!(progress_color(&playing(50)), Color::Yellow);
+        assert_eq!(progress_color(&playing(58)), Color::Red);
+        assert_eq!(playing(90).remaining(), Duration::ZERO);
+
+        let mut looping = playing(58);
+        looping.repeat = true;
+        assert_eq!(progress_color(&looping), Color::Blue);
+    }
+
+    #[test]
+    fn fade_and_loop_state() {
+        let mut meta = playing(2);
+        assert!(!meta.is_fading());
+
+        meta.fade_in = Some(Duration::from_secs(5));
+        assert!(meta.is_fading());
+        assert_eq!(text(&meta), "SQ1  80% fading");
+
+        // Only the first time through fades in
+        meta.repeat = true;
+        meta.loops = 2;
+        meta.track = Some("walk-in.wav".to_string());
+        assert!(!meta.is_fading());
+        assert_eq!(text(&meta), "SQ1  80% loop 3 walk-in.wav");
+
+        let mut ending = playing(57);
+        ending.fade_out = Some(Duration::from_secs(5));
+        assert!(ending.is_fading());
+
+        let mut faded = playing(30);
+        faded.fading = true;
+        assert!(faded.is_fading());
+    }
+}
@@ -0,0 +1,68 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, StatefulWidget, Widget};
+use time::macros::format_description;
+
+use crate::app::clock::ShowClock;
+use crate::util::format;
+
+#[derive(Debug, Default)]
+pub struct Clock<'b> {
+    block: Option<Block<'b>>,
+}
+
+impl<'b> Clock<'b> {
+    pub fn block(mut self, block: Block<'b>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl StatefulWidget for Clock<'_> {
+    type State = ShowClock;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let wall = state
+            .now()
+            .time()
+            .format(format_description!("[hour]:[minute]:[second]"))
+            .unwrap_or(String::from("--:--:--"));
+
+        let timer = |name: &'static str, d: Option<std::time::Duration>| {
+            Line::from(vec![
+                Span::from(format!("{name:<10}")).dark_gray(),
+                Span::from(d.map_or(String::from("--:--:--"), format::hh_mm_ss)),
+            ])
+        };
+
+        let mut lines = vec![
+            Line::from(Span::from(wall).bold()),
+            timer("Show", state.show_elapsed()),
+            timer("Last GO", state.since_last_go()),
+            timer("Act", state.act_elapsed()),
+        ];
+
+        if let Some((name, remaining)) = state.countdown() {
+            lines.push(Line::from(vec![
+                Span::from(format!("{name} in ")).dark_gray(),
+                Span::from(format::hh_mm_ss(remaining)).yellow(),
+            ]));
+        }
+
+        let mut paragraph = Paragraph::new(lines).alignment(Alignment::Center);
+
+        if let Some(b) = self.block {
+            paragraph = paragraph.block(b)
+        }
+
+        Widget::render(paragraph, area, buf)
+    }
+}
+
+pub fn clock() -> Clock<'static> {
+    Clock::default().block(
+        Block::new()
+            .borders(Borders::all())
+            .border_type(BorderType::Rounded)
+            .title("Clock"),
+    )
+}
@@ -1,33 +1,196 @@
+use std::{collections::HashSet, ops::Range};
+
 use ratatui::{
     prelude::Constraint,
     style::{Color, Style, Stylize},
+    text::{Line, Span, Text},
     widgets::{Block, BorderType, Borders, Cell, Row, Table},
 };
 
-use crate::cues::Cue;
+use crate::app::{rehearsal::Wait, state::LoadStatus};
+use crate::cues::{Cue, Script, Section};
+use crate::util::format;
+
+/// A row of the cue list: either a cue, or the header of a section
+#[derive(Debug, Clone)]
+pub enum ListRow<'a> {
+    Section {
+        section: &'a Section,
+        cues: Range<usize>,
+        collapsed: bool,
+    },
+    Cue(usize),
+}
+
+impl ListRow<'_> {
+    /// Whether this row stands for the cue at `idx`, either directly or as
+    /// the header of the collapsed section it's in
+    pub fn shows(&self, idx: usize) -> bool {
+        match self {
+            ListRow::Section {
+                cues, collapsed, ..
+            } => *collapsed && cues.contains(&idx),
+            ListRow::Cue(i) => *i == idx,
+        }
+    }
+}
+
+/// Lay out the cue list with a header before each section, leaving out
+/// the cues of `collapsed` sections
+pub fn list_rows<'a>(script: &'a Script, collapsed: &HashSet<String>) -> Vec<ListRow<'a>> {
+    let len = script.cuelist().len();
+    let starts = script.section_starts();
+    let mut rows = Vec::with_capacity(len + starts.len());
+
+    let first = starts.first().map_or(len, |(i, _)| *i);
+    rows.extend((0..first).map(ListRow::Cue));
+
+    for (n, (start, section)) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).map_or(len, |(i, _)| *i);
+        let is_collapsed = collapsed.contains(section.name());
+
+        rows.push(ListRow::Section {
+            section,
+            cues: *start..end,
+            collapsed: is_collapsed,
+        });
+
+        if !is_collapsed {
+            rows.extend((*start..end).map(ListRow::Cue));
+        }
+    }
+
+    rows
+}
+
+fn status_cell(status: &LoadStatus) -> Cell<'static> {
+    match status {
+        LoadStatus::Loaded => Cell::from("✔").green(),
+        LoadStatus::Failed(_) => Cell::from("✘").red(),
+        LoadStatus::Loading => Cell::from("…").yellow(),
+        LoadStatus::NotLoaded => Cell::from("·").dark_gray(),
+        LoadStatus::Running => Cell::from("▶").cyan(),
+    }
+}
 
-pub fn cue_list(cuelist: &[Cue]) -> Table {
-    let items: Vec<_> = cuelist
+/// The usual wait before the cue, or how far off it is this run
+fn wait_cell(wait: &Wait) -> Cell<'static> {
+    let secs = |d: std::time::Duration| d.as_secs_f64();
+
+    match *wait {
+        Wait::Unknown => Cell::from(""),
+        Wait::Expected(expected) => Cell::from(format::mm_ss(expected)).dark_gray(),
+        Wait::Running { expected, elapsed } if elapsed > expected => Cell::from(format!(
+            "{} long",
+            format::difference(secs(elapsed - expected))
+        ))
+        .red(),
+        Wait::Running { expected, elapsed } => {
+            Cell::from(format!("{} left", format::mm_ss(expected - elapsed))).yellow()
+        }
+        Wait::Fired { expected, actual } => {
+            let diff = secs(actual) - secs(expected);
+            let cell = Cell::from(format!(
+                "{} {}",
+                format::mm_ss(actual),
+                format::difference(diff)
+            ));
+            match diff {
+                d if d >= 5.0 => cell.red(),
+                d if d <= -5.0 => cell.cyan(),
+                _ => cell.green(),
+            }
+        }
+    }
+}
+
+fn cue_row<'a>(cue: &'a Cue, status: &LoadStatus, wait: &Wait, show_errors: bool) -> Row<'a> {
+    let mut hint = Text::from(cue.hint());
+    let mut height = 1;
+
+    if let (true, LoadStatus::Failed(e)) = (show_errors, status) {
+        hint.extend([Line::from(Span::from(e.clone()).red())]);
+        height = 2;
+    }
+
+    Row::new(vec![
+        status_cell(status),
+        Cell::from(cue.number().map(|n| n.to_string()).unwrap_or_default()),
+        Cell::from(cue.label()),
+        Cell::from(cue.description()),
+        wait_cell(wait),
+        Cell::from(hint),
+    ])
+    .height(height)
+}
+
+fn section_row<'a>(section: &'a Section, cues: &Range<usize>, collapsed: bool) -> Row<'a> {
+    let mut details = format!("{} cues", cues.len());
+    if let Some(volume) = section.volume() {
+        details.push_str(&format!(", volume {volume}%"));
+    }
+
+    Row::new(vec![
+        Cell::from(if collapsed { "▸" } else { "▾" }),
+        Cell::from(""),
+        Cell::from(""),
+        Cell::from(section.name()),
+        Cell::from(""),
+        Cell::from(Span::from(details).dark_gray()),
+    ])
+    .style(Style::new().bold().fg(Color::Cyan))
+}
+
+pub fn cue_list<'a>(
+    cuelist: &'a [Cue],
+    rows: &[ListRow<'a>],
+    statuses: Vec<LoadStatus>,
+    waits: Vec<Wait>,
+    show_errors: bool,
+    (loaded, total): (usize, usize),
+    mode: Option<&str>,
+) -> Table<'a> {
+    let mut title = String::from("Cues");
+    if loaded < total {
+        title.push_str(&format!(" (loading {loaded}/{total})"));
+    }
+    if let Some(mode) = mode {
+        title.push_str(&format!(" [{mode}]"));
+    }
+
+    let items: Vec<_> = rows
         .iter()
-        .map(|c| {
-            Row::new(vec![
-                Cell::from(c.label()),
-                Cell::from(c.description()),
-                Cell::from(c.hint()),
-            ])
+        .filter_map(|row| match row {
+            ListRow::Section {
+                section,
+                cues,
+                collapsed,
+            } => Some(section_row(section, cues, *collapsed)),
+            ListRow::Cue(i) => Some(cue_row(
+                cuelist.get(*i)?,
+                statuses.get(*i)?,
+                waits.get(*i)?,
+                show_errors,
+            )),
         })
         .collect();
 
     Table::new(items)
         .widths(&[
+            Constraint::Length(1),
+            Constraint::Length(6),
             Constraint::Length(10),
             Constraint::Length(25),
+            Constraint::Length(12),
             Constraint::Percentage(100),
         ])
         .header(
             Row::new(vec![
+                Cell::from(""),
+                Cell::from("#"),
                 Cell::from("Label"),
                 Cell::from("Description"),
+                Cell::from("Wait"),
                 Cell::from("Cue"),
             ])
             .style(Style::new().bold().underlined()),
@@ -38,6 +201,6 @@ pub fn cue_list(cuelist: &[Cue]) -> Table {
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .title("Cues"),
+                .title(title),
         )
 }
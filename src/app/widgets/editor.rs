@@ -0,0 +1,40 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Widget, Wrap};
+
+use crate::app::editor::CueForm;
+
+pub fn cue_form(form: &CueForm) -> impl Widget + '_ {
+    let mut lines: Vec<_> = form
+        .fields()
+        .map(|(field, value, focused)| {
+            let name = Span::from(format!("{:>12}: ", field.name())).dark_gray();
+
+            if focused {
+                Line::from(vec![
+                    name,
+                    Span::from(value).bold(),
+                    Span::from("▏").yellow(),
+                ])
+            } else {
+                Line::from(vec![name, Span::from(value)])
+            }
+        })
+        .collect();
+
+    lines.push(Line::default());
+    lines.push(match form.error() {
+        Some(e) => Line::from(Span::from(e).red()),
+        None => Line::from(Span::from("✔ Valid").green()),
+    });
+    lines.push(Line::default());
+    lines.push(Line::from(
+        Span::from("[Tab] Next Field | [Enter] Apply | [Esc] Cancel").dark_gray(),
+    ));
+
+    Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(form.title()),
+    )
+}
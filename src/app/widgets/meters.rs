@@ -0,0 +1,155 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, BorderType, Borders, StatefulWidget, Widget};
+
+use crate::sound::{meter::ChannelLevel, AudioEngine};
+
+/// Lowest level shown on the meters, in dBFS
+const FLOOR_DB: f32 = -60.0;
+
+#[derive(Debug, Default)]
+pub struct OutputMeters<'b> {
+    block: Option<Block<'b>>,
+}
+
+impl<'b> OutputMeters<'b> {
+    pub fn block(mut self, block: Block<'b>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+fn to_db(level: f32) -> f32 {
+    (20.0 * level.log10()).max(FLOOR_DB)
+}
+
+/// Fraction of the meter width that a level fills
+fn to_ratio(level: f32) -> f32 {
+    1.0 - to_db(level) / FLOOR_DB
+}
+
+fn level_color(db: f32) -> Color {
+    if db > -6.0 {
+        Color::Red
+    } else if db > -18.0 {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
+
+fn channel_name(channel: usize, channels: usize) -> String {
+    match (channels, channel) {
+        (2, 0) => String::from("L"),
+        (2, 1) => String::from("R"),
+        _ => (channel + 1).to_string(),
+    }
+}
+
+fn render_channel(name: &str, level: &ChannelLevel, area: Rect, buf: &mut Buffer) {
+    // "L " + bar + " -12.3 CLIP"
+    let bar_width = area.width.saturating_sub(13);
+
+    buf.set_string(area.left(), area.top(), name, Style::new().bold());
+
+    let bar_left = area.left() + 2;
+    let rms_cells = (to_ratio(level.rms) * bar_width as f32).round() as u16;
+    let peak_cells = (to_ratio(level.peak) * bar_width as f32).round() as u16;
+    let hold_cell = (to_ratio(level.hold) * bar_width as f32).round() as u16;
+
+    for x in 0..bar_width {
+        let db = FLOOR_DB * (1.0 - (x + 1) as f32 / bar_width as f32);
+        let color = level_color(db);
+
+        let (symbol, style) = if x < rms_cells {
+            ("█", Style::new().fg(color))
+        } else if x < peak_cells {
+            ("▒", Style::new().fg(color))
+        } else if hold_cell > 0 && x == hold_cell - 1 {
+            ("│", Style::new().fg(color))
+        } else {
+            ("·", Style::new().dark_gray())
+        };
+
+        buf.set_string(bar_left + x, area.top(), symbol, style);
+    }
+
+    let db = to_db(level.peak);
+    let readout = if db <= FLOOR_DB {
+        String::from("  -inf")
+    } else {
+        format!("{db:>6.1}")
+    };
+    // Leave out what doesn't fit rather than draw over the next panel
+    let readout_left = bar_left + bar_width;
+    if readout_left + 6 <= area.right() {
+        buf.set_string(readout_left, area.top(), readout, Style::new());
+    }
+
+    if level.clipped() && readout_left + 11 <= area.right() {
+        buf.set_string(
+            readout_left + 7,
+            area.top(),
+            "CLIP",
+            Style::new().white().on_red().bold(),
+        );
+    }
+}
+
+impl StatefulWidget for OutputMeters<'_> {
+    type State = AudioEngine;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = match self.block {
+            Some(b) => {
+                let inner = b.inner(area);
+                b.render(area, buf);
+                inner
+            }
+            None => area,
+        };
+
+        let levels = state.meter().levels();
+
+        for (i, level) in levels.iter().enumerate().take(area.height as usize) {
+            let row = Rect {
+                y: area.y + i as u16,
+                height: 1,
+                ..area
+            };
+
+            render_channel(&channel_name(i, levels.len()), level, row, buf);
+        }
+    }
+}
+
+pub fn output_meters() -> OutputMeters<'static> {
+    OutputMeters::default().block(
+        Block::new()
+            .borders(Borders::all())
+            .border_type(BorderType::Rounded)
+            .title("Output"),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use ratatui::prelude::*;
+
+    use super::render_channel;
+    use crate::sound::meter::ChannelLevel;
+
+    fn row(width: u16) -> String {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 20, 1));
+        let mut level = ChannelLevel::default();
+        level.peak = 0.5;
+        render_channel("L", &level, Rect::new(0, 0, width, 1), &mut buf);
+        buf.content.iter().map(|c| c.symbol.as_str()).collect()
+    }
+
+    #[test]
+    fn narrow_meter_stays_in_its_area() {
+        assert_eq!(row(14), "L ▒  -6.0           ");
+        assert_eq!(row(8), "L   -6.0            ");
+        assert_eq!(row(6), "L                   ");
+    }
+}
@@ -1,5 +1,15 @@
 mod active_list;
+mod clock;
 mod cuelist;
+mod editor;
+mod meters;
+mod spectrum;
+mod standby;
 
 pub use active_list::active_cues;
-pub use cuelist::cue_list;
+pub use clock::clock;
+pub use cuelist::{cue_list, list_rows, ListRow};
+pub use editor::cue_form;
+pub use meters::output_meters;
+pub use spectrum::spectrum;
+pub use standby::standby;
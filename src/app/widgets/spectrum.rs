@@ -0,0 +1,70 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, BorderType, Borders, Widget};
+
+use crate::sound::spectrum::FLOOR_DB;
+
+/// Range of levels shown on the analyzer, in dB above [`FLOOR_DB`]
+const RANGE: f32 = -FLOOR_DB;
+
+#[derive(Debug, Default)]
+pub struct Spectrum<'b> {
+    levels: Vec<f32>,
+    block: Option<Block<'b>>,
+}
+
+impl<'b> Spectrum<'b> {
+    pub fn levels(mut self, levels: Vec<f32>) -> Self {
+        self.levels = levels;
+        self
+    }
+
+    pub fn block(mut self, block: Block<'b>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+fn bar_color(db: f32) -> Color {
+    if db > -6.0 {
+        Color::Red
+    } else if db > -18.0 {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
+
+impl Widget for Spectrum<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let bars: Vec<_> = self
+            .levels
+            .iter()
+            .map(|db| {
+                Bar::default()
+                    .value((db - FLOOR_DB).clamp(0.0, RANGE) as u64)
+                    .style(Style::new().fg(bar_color(*db)))
+            })
+            .collect();
+
+        let mut chart = BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(1)
+            .bar_gap(0)
+            .max(RANGE as u64);
+
+        if let Some(b) = self.block {
+            chart = chart.block(b)
+        }
+
+        chart.render(area, buf)
+    }
+}
+
+pub fn spectrum<'b>(title: String) -> Spectrum<'b> {
+    Spectrum::default().block(
+        Block::new()
+            .borders(Borders::all())
+            .border_type(BorderType::Rounded)
+            .title(title),
+    )
+}
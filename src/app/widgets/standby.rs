@@ -0,0 +1,98 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Widget, Wrap};
+
+use crate::app::state::LoadStatus;
+use crate::cues::Cue;
+
+#[derive(Debug, Default)]
+pub struct StandbyPanel<'a> {
+    cue: Option<&'a Cue>,
+    status: Option<LoadStatus>,
+    next: Option<&'a Cue>,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> StandbyPanel<'a> {
+    pub fn cue(mut self, cue: &'a Cue, status: LoadStatus) -> Self {
+        self.cue = Some(cue);
+        self.status = Some(status);
+        self
+    }
+
+    pub fn next(mut self, next: Option<&'a Cue>) -> Self {
+        self.next = next;
+        self
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl Widget for StandbyPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = Vec::new();
+
+        if let Some(cue) = self.cue {
+            let status = match &self.status {
+                Some(LoadStatus::Loaded) => Span::from("Loaded").green(),
+                Some(LoadStatus::Failed(_)) => Span::from("Failed").red(),
+                Some(LoadStatus::Running) => Span::from("Running").cyan(),
+                Some(LoadStatus::Loading) => Span::from("Loading").yellow(),
+                Some(LoadStatus::NotLoaded) | None => Span::from("Not loaded").dark_gray(),
+            };
+
+            lines.push(Line::from(vec![
+                Span::from(cue.label().to_string()).bold(),
+                Span::from(" "),
+                status,
+            ]));
+
+            if !cue.description().is_empty() {
+                lines.push(Line::from(cue.description().to_string()));
+            }
+            if !cue.hint().is_empty() {
+                lines.push(Line::from(vec![
+                    Span::from("Cue: ").dark_gray(),
+                    Span::from(cue.hint().to_string()).italic(),
+                ]));
+            }
+
+            lines.push(Line::from(cue.action().to_string()));
+
+            if let Some(LoadStatus::Failed(e)) = &self.status {
+                lines.push(Line::from(Span::from(e.clone()).red()));
+            }
+        } else {
+            lines.push(Line::from(Span::from("No cue selected").dark_gray()));
+        }
+
+        if let Some(next) = self.next {
+            lines.push(Line::default());
+            lines.push(Line::from(vec![
+                Span::from("Next: ").dark_gray(),
+                Span::from(next.label().to_string()).bold(),
+                Span::from(" "),
+                Span::from(next.description().to_string()),
+            ]));
+        }
+
+        let mut paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
+
+        if let Some(b) = self.block {
+            paragraph = paragraph.block(b)
+        }
+
+        paragraph.render(area, buf)
+    }
+}
+
+pub fn standby<'a>() -> StandbyPanel<'a> {
+    StandbyPanel::default().block(
+        Block::new()
+            .borders(Borders::all())
+            .border_type(BorderType::Rounded)
+            .title("Standby"),
+    )
+}
@@ -0,0 +1,259 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, Subcommand};
+
+use crate::cues::{script_schema, CsvMapping, CueSheet, Script, SheetFormat};
+use crate::logging::{latest_log, LogLevels, ReportFormat, ShowReport};
+use crate::prelude::*;
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Script file to use; the format is picked by extension (.yaml, .json or .toml)
+    #[arg(long, short, global = true)]
+    pub script: Option<PathBuf>,
+
+    /// Log level, optionally per module, like `warn,encue::cues::actions::fade=trace`
+    #[arg(long, global = true, env = "ENCUE_LOG")]
+    pub log: Option<LogLevels>,
+
+    /// Record how long after the previous GO each GO happens, to compare later runs with
+    #[arg(long, global = true)]
+    pub rehearsal: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    pub fn script_path(&self) -> PathBuf {
+        self.script.clone().unwrap_or_else(Script::default_path)
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Reorder the cue list by cue number
+    Sort,
+
+    /// Give every cue a whole number, in cue list order
+    Renumber {
+        /// Number of the first cue
+        #[arg(long, default_value_t = 1)]
+        start: u32,
+
+        /// Difference between consecutive cue numbers
+        #[arg(long, default_value_t = 1)]
+        step: u32,
+    },
+
+    /// Write the script in another format, picked by the extension of `output`
+    Convert { output: PathBuf },
+
+    /// Create the script from a cue sheet made somewhere else
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+
+    /// Write a printable cue sheet listing every cue
+    Export {
+        /// Where to write the cue sheet; the format is picked by extension
+        /// (.md, .html or .csv) unless `--format` is given
+        output: PathBuf,
+
+        #[arg(long, short)]
+        format: Option<SheetFormat>,
+    },
+
+    /// Print the JSON Schema for script files, or write it to `output`
+    Schema { output: Option<PathBuf> },
+
+    /// Summarize a performance from its show log, comparing it with earlier ones
+    Report {
+        /// Show log to report on, the newest one by default
+        log: Option<PathBuf>,
+
+        /// Where to write the report, instead of printing it
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// Format of the report, picked by the extension of `output` (.md or .json) by default
+        #[arg(long, short)]
+        format: Option<ReportFormat>,
+
+        /// Seconds longer than usual a wait between GOs must be to count as a hold
+        #[arg(long, default_value_t = 120.0)]
+        hold: f64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportSource {
+    /// Import a spreadsheet saved as CSV, with a header row naming the columns
+    Csv {
+        input: PathBuf,
+
+        /// File saying which columns hold which cue fields
+        #[arg(long, short)]
+        mapping: Option<PathBuf>,
+
+        /// Replace the script if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+impl Command {
+    pub fn run(self, path: &Path) -> Result<()> {
+        let script = match self {
+            Command::Sort => Script::load_from(path)?.sort_by_number(),
+            Command::Renumber { start, step } => Script::load_from(path)?.renumber(start, step),
+            Command::Convert { output } => return convert(Script::load_from(path)?, path, &output),
+            Command::Import { source } => return source.run(path),
+            Command::Export { output, format } => {
+                return export(Script::load_from(path)?.validate()?, path, &output, format)
+            }
+            Command::Schema { output } => return schema(output.as_deref()),
+            Command::Report {
+                log,
+                output,
+                format,
+                hold,
+            } => {
+                let script = Script::load_from(path)?.validate()?;
+                let log = match log {
+                    Some(log) => log,
+                    None => latest_log(&script.logs().dir_for(path))?,
+                };
+                return report(&script, &log, output.as_deref(), format, hold);
+            }
+        }
+        .validate_without_files()?;
+
+        script.save_to(path)?;
+        println!(
+            "Updated {} cues in {}",
+            script.cuelist().len(),
+            path.display()
+        );
+
+        Ok(())
+    }
+}
+
+impl ImportSource {
+    pub fn run(self, path: &Path) -> Result<()> {
+        let ImportSource::Csv {
+            input,
+            mapping,
+            force,
+        } = self;
+
+        if path.exists() && !force {
+            return Err(FatalError::Exists(path.to_path_buf()));
+        }
+
+        let mapping = match mapping {
+            Some(mapping) => serde_yaml::from_value(Script::read_raw(mapping)?)?,
+            None => CsvMapping::default(),
+        };
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let import = mapping.import(fs::File::open(&input)?, dir)?;
+
+        for column in &import.missing {
+            eprintln!("Column {column} isn't in {}", input.display());
+        }
+        for (row, e) in &import.skipped {
+            eprintln!("Skipped row {row}: {e}");
+        }
+
+        import.script.save_to(path)?;
+        println!(
+            "Imported {} cues from {} to {}, skipped {} rows",
+            import.script.cuelist().len(),
+            input.display(),
+            path.display(),
+            import.skipped.len()
+        );
+
+        Ok(())
+    }
+}
+
+/// Rewrite the main script file in the format of `output`, keeping its
+/// templates and includes as they are
+fn convert(script: Script, path: &Path, output: &Path) -> Result<()> {
+    script.validate()?;
+
+    let value = Script::read_raw(path)?;
+    Script::write_raw(output, &value)?;
+
+    // Make sure nothing was lost, e.g. includes that no longer resolve from the new location
+    let converted = Script::load_from(output)?.validate()?;
+    println!(
+        "Converted {} cues from {} to {}",
+        converted.cuelist().len(),
+        path.display(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+fn export(script: Script, path: &Path, output: &Path, format: Option<SheetFormat>) -> Result<()> {
+    let format = match format {
+        Some(format) => format,
+        None => SheetFormat::from_path(output)?,
+    };
+
+    let title = path.file_stem().unwrap_or_default().to_string_lossy();
+    let sheet = CueSheet::new(format!("Cue sheet: {title}"), &script);
+    fs::write(output, sheet.render(format)?)?;
+    println!("Wrote {} cues to {}", sheet.rows().len(), output.display());
+
+    Ok(())
+}
+
+fn schema(output: Option<&Path>) -> Result<()> {
+    let schema = serde_json::to_string_pretty(&script_schema())?;
+
+    match output {
+        Some(output) => {
+            fs::write(output, schema + "\n")?;
+            println!("Wrote script schema to {}", output.display());
+        }
+        None => println!("{schema}"),
+    }
+
+    Ok(())
+}
+
+fn report(
+    script: &Script,
+    log: &Path,
+    output: Option<&Path>,
+    format: Option<ReportFormat>,
+    hold: f64,
+) -> Result<()> {
+    let format = match (format, output) {
+        (Some(format), _) => format,
+        (None, Some(output)) => ReportFormat::from_path(output)?,
+        (None, None) => ReportFormat::Markdown,
+    };
+
+    let report = ShowReport::from_log(log, script, hold)?.render(format)?;
+
+    match output {
+        Some(output) => {
+            fs::write(output, report)?;
+            println!("Wrote report on {} to {}", log.display(), output.display());
+        }
+        None => print!("{report}"),
+    }
+
+    Ok(())
+}
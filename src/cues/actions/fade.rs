@@ -1,25 +1,77 @@
-use std::{convert::Infallible, ops::Mul, str::FromStr, thread, time::Duration};
+use std::{convert::Infallible, fmt::Display, ops::Mul, str::FromStr, thread, time::Duration};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use crate::{
     sound::{ExecuteCue, ExecuteCueError},
-    util::defaults,
+    util::{
+        defaults, format,
+        serde::{is_default, string_or_struct::ShortForm},
+    },
 };
 
+/// Shape of the volume change over the course of a fade
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FadeCurve {
+    #[default]
+    Linear,
+    /// Eases in and out of the fade
+    SCurve,
+    /// Changes by the same number of decibels every step, which sounds
+    /// more even than a linear fade
+    Exponential,
+}
+
+/// Quietest level an exponential fade works with, in dB; anything below is treated as silence
+const CURVE_FLOOR_DB: f32 = -60.0;
+
+impl FadeCurve {
+    /// Volume `t` of the way (0 to 1) through a fade from `from` to `to`
+    pub fn volume(&self, from: f32, to: f32, t: f32) -> f32 {
+        match self {
+            FadeCurve::Linear => from + (to - from) * t,
+            FadeCurve::SCurve => from + (to - from) * t * t * (3.0 - 2.0 * t),
+            FadeCurve::Exponential => {
+                let db = |v: f32| (20.0 * v.log10()).max(CURVE_FLOOR_DB);
+                let (a, b) = (db(from), db(to));
+                let level = a + (b - a) * t;
+
+                if level <= CURVE_FLOOR_DB && t >= 1.0 {
+                    to
+                } else {
+                    10f32.powf(level / 20.0)
+                }
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            FadeCurve::Linear => "linear",
+            FadeCurve::SCurve => "s-curve",
+            FadeCurve::Exponential => "exponential",
+        }
+    }
+}
+
 #[serde_as]
-#[cfg_attr(test, derive(Eq, PartialEq))]
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 pub struct FadeCue {
     target: String,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     volume: u8,
 
     #[serde_as(as = "serde_with::DurationSecondsWithFrac")]
+    #[schemars(with = "f64")]
     #[serde(default = "defaults::default_fade_duration")]
     duration: Duration,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    curve: FadeCurve,
 }
 
 impl FadeCue {
@@ -34,6 +86,10 @@ impl FadeCue {
     pub fn duration(&self) -> &Duration {
         &self.duration
     }
+
+    pub fn curve(&self) -> FadeCurve {
+        self.curve
+    }
 }
 
 #[allow(dead_code)]
@@ -43,6 +99,7 @@ impl FadeCue {
             target: target.to_string(),
             volume: Default::default(),
             duration: defaults::default_fade_duration(),
+            curve: FadeCurve::Linear,
         }
     }
 
@@ -55,6 +112,11 @@ impl FadeCue {
         self.duration = duration.into();
         self
     }
+
+    pub fn with_curve(mut self, curve: FadeCurve) -> Self {
+        self.curve = curve;
+        self
+    }
 }
 
 impl FromStr for FadeCue {
@@ -65,11 +127,36 @@ impl FromStr for FadeCue {
     }
 }
 
+impl ShortForm for FadeCue {
+    fn short_form(&self) -> Option<String> {
+        (*self == Self::new(self.target())).then(|| self.target().to_string())
+    }
+}
+
+impl Display for FadeCue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Fade {} to {}% over {}",
+            self.target(),
+            self.volume(),
+            format::secs(*self.duration())
+        )?;
+
+        if self.curve() != FadeCurve::Linear {
+            write!(f, ", {}", self.curve().name())?;
+        }
+
+        Ok(())
+    }
+}
+
 impl ExecuteCue for FadeCue {
     fn execute(self, engine: &mut crate::sound::AudioEngine) -> Result<(), ExecuteCueError> {
         let Some(sink) = engine.get_sink(self.target()) else {
             return Err(ExecuteCueError::MissingTarget(self.target))
         };
+        let meta = engine.get_meta(self.target());
 
         let initial_vol = sink.volume().mul(100_f32).round() as i32;
 
@@ -77,11 +164,13 @@ impl ExecuteCue for FadeCue {
 
         let steps = initial_vol
             .checked_sub(target_vol)
-            .ok_or(ExecuteCueError::General("overflow"))?;
+            .ok_or(ExecuteCueError::General("overflow"))?
+            .unsigned_abs()
+            .max(1);
 
         let fade_rate = self
             .duration()
-            .checked_div(steps.unsigned_abs())
+            .checked_div(steps)
             .unwrap_or(self.duration().clone());
 
         log::trace!(
@@ -90,21 +179,31 @@ impl ExecuteCue for FadeCue {
             fade_rate.as_millis()
         );
 
-        let vols = if target_vol > initial_vol {
-            itertools::Either::Left(initial_vol..=target_vol)
-        } else {
-            itertools::Either::Right((target_vol..=initial_vol).rev())
+        let (from, to, curve) = (
+            initial_vol as f32 / 100.0,
+            target_vol as f32 / 100.0,
+            self.curve(),
+        );
+        let vols = (1..=steps).map(move |i| curve.volume(from, to, i as f32 / steps as f32));
+
+        let set_fading = move |fading: bool| {
+            if let Some(Ok(mut meta)) = meta.as_ref().map(|m| m.lock()) {
+                meta.fading = fading;
+            }
         };
 
         thread::spawn(move || {
+            set_fading(true);
+
             for current_vol in vols {
                 thread::sleep(fade_rate);
                 log::trace!("fading {} to {current_vol}", self.target());
 
-                let current_vol = (current_vol as f32) / 100.0;
                 sink.set_volume(current_vol)
             }
 
+            set_fading(false);
+
             if self.volume() == 0 {
                 sink.stop();
             }
@@ -3,7 +3,7 @@ mod playback;
 mod playlist;
 mod stop;
 
-pub use fade::FadeCue;
+pub use fade::{FadeCue, FadeCurve};
 pub use playback::PlaybackCue;
 pub use playlist::PlaylistCue;
 pub use stop::StopCue;
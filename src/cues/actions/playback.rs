@@ -1,5 +1,6 @@
 use std::{
     convert::Infallible,
+    fmt::Display,
     io::{self, BufReader, Seek},
     path::{Path, PathBuf},
     str::FromStr,
@@ -9,32 +10,60 @@ use std::{
 
 use lofty::AudioFile;
 use rodio::{Decoder, Sink, Source};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use thiserror::Error;
 
-use crate::sound::{metadata::PlaybackMeta, PlaybackExecutable, PrepareCue};
+use crate::{
+    sound::{
+        cache::{self, BufferSource, DecodeError},
+        metadata::PlaybackMeta,
+        PlaybackExecutable, PrepareCue,
+    },
+    util::{format, serde::is_default, serde::string_or_struct::ShortForm},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Preload {
+    /// Stream the file from disk each time the cue is fired
+    #[default]
+    Stream,
+
+    /// Decode the whole file into memory once and share it between fires
+    Memory,
+}
 
 #[serde_as]
-#[cfg_attr(test, derive(Eq, PartialEq))]
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 pub struct PlaybackCue {
     file: PathBuf,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     volume: Option<u8>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     #[serde(alias = "loop")]
     repeat: bool,
 
     #[serde_as(as = "Option<serde_with::DurationSecondsWithFrac>")]
+    #[schemars(with = "Option<f64>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     duration: Option<Duration>,
 
     #[serde_as(as = "Option<serde_with::DurationSecondsWithFrac>")]
+    #[schemars(with = "Option<f64>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     fade_in: Option<Duration>,
 
     #[serde_as(as = "Option<serde_with::DurationSecondsWithFrac>")]
+    #[schemars(with = "Option<f64>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     fade_out: Option<Duration>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    preload: Preload,
 }
 
 impl PlaybackCue {
@@ -53,6 +82,14 @@ impl PlaybackCue {
     pub fn fade_out(&self) -> Option<Duration> {
         self.fade_out
     }
+
+    pub fn volume(&self) -> Option<u8> {
+        self.volume
+    }
+
+    pub fn repeat(&self) -> bool {
+        self.repeat
+    }
 }
 
 #[allow(dead_code)]
@@ -65,6 +102,7 @@ impl PlaybackCue {
             volume: None,
             fade_in: None,
             fade_out: None,
+            preload: Preload::Stream,
         }
     }
 
@@ -82,6 +120,16 @@ impl PlaybackCue {
         self.fade_out = Some(duration.into());
         self
     }
+
+    pub fn with_volume(mut self, volume: u8) -> Self {
+        self.volume = Some(volume);
+        self
+    }
+
+    pub fn preload_into(mut self, preload: Preload) -> Self {
+        self.preload = preload;
+        self
+    }
 }
 
 impl FromStr for PlaybackCue {
@@ -92,6 +140,40 @@ impl FromStr for PlaybackCue {
     }
 }
 
+impl ShortForm for PlaybackCue {
+    fn short_form(&self) -> Option<String> {
+        let file = self.file().to_str()?;
+        (*self == Self::new(file)).then(|| file.to_string())
+    }
+}
+
+impl Display for PlaybackCue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Play {}", self.file().display())?;
+
+        if let Some(vol) = self.volume {
+            write!(f, " at {vol}%")?;
+        }
+        if self.repeat {
+            write!(f, ", looped")?;
+        }
+        if let Some(d) = self.duration() {
+            write!(f, ", first {}", format::secs(d))?;
+        }
+        if let Some(d) = self.fade_in() {
+            write!(f, ", fade in {}", format::secs(d))?;
+        }
+        if let Some(d) = self.fade_out() {
+            write!(f, ", fade out {}", format::secs(d))?;
+        }
+        if let Preload::Memory = self.preload {
+            write!(f, ", in memory")?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum PlaybackCueError {
     #[error(transparent)]
@@ -102,6 +184,9 @@ pub enum PlaybackCueError {
 
     #[error("Error retrieving metadata: {0}")]
     Metadata(#[from] lofty::LoftyError),
+
+    #[error(transparent)]
+    Preload(#[from] DecodeError),
 }
 
 impl PrepareCue for PlaybackCue {
@@ -110,21 +195,42 @@ impl PrepareCue for PlaybackCue {
     type PrepareError = PlaybackCueError;
 
     fn prepare(&self, label: Option<&str>) -> Result<Self::Executable, Self::PrepareError> {
-        let mut f = std::fs::File::open(self.file())?;
-
-        let duration = match self.duration() {
-            Some(d) => d,
-            None => lofty::read_from(&mut f)?.properties().duration(),
+        let (mut s, duration): (Box<dyn Source<Item = f32> + Send + Sync>, _) = match self.preload {
+            Preload::Memory => {
+                let audio = cache::load(self.file())?;
+                let duration = self.duration().unwrap_or_else(|| audio.duration());
+
+                (
+                    Box::new(BufferSource::new(audio).take_duration(duration)),
+                    duration,
+                )
+            }
+            Preload::Stream => {
+                let mut f = std::fs::File::open(self.file())?;
+
+                let duration = match self.duration() {
+                    Some(d) => d,
+                    None => lofty::read_from(&mut f)?.properties().duration(),
+                };
+
+                f.seek(io::SeekFrom::Start(0))?;
+
+                (
+                    Box::new(
+                        Decoder::new(BufReader::new(f))?
+                            .convert_samples()
+                            .take_duration(duration),
+                    ),
+                    duration,
+                )
+            }
         };
 
-        f.seek(io::SeekFrom::Start(0))?;
-
-        let mut s: Box<dyn Source<Item = i16> + Send + Sync> =
-            Box::new(Decoder::new(BufReader::new(f))?.take_duration(duration));
-
         let meta = Arc::new(Mutex::new(PlaybackMeta {
-            start: Instant::now(),
-            duration,
+            fade_in: self.fade_in(),
+            fade_out: self.fade_out(),
+            repeat: self.repeat,
+            ..PlaybackMeta::new(duration)
         }));
 
         if self.repeat {
@@ -139,8 +245,14 @@ impl PrepareCue for PlaybackCue {
 
         // TODO: This step causes a panic in some circumstances. Need to investigate further
         let meta_mut = meta.clone();
+        let mut started = false;
         s = Box::new(s.periodic_access(duration, move |_| {
-            meta_mut.lock().unwrap().start = Instant::now();
+            let mut meta = meta_mut.lock().unwrap();
+            if started {
+                meta.loops += 1;
+            }
+            meta.start = Instant::now();
+            started = true;
         }));
 
         if let Some(fade_duration) = self.fade_out() {
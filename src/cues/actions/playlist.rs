@@ -1,5 +1,6 @@
 use std::{
     convert::Infallible,
+    fmt::Display,
     io::{self, BufReader, Seek},
     path::{Path, PathBuf},
     str::FromStr,
@@ -11,31 +12,38 @@ use itertools::Either;
 use lofty::AudioFile;
 use rand::{seq::SliceRandom, thread_rng};
 use rodio::{source, Decoder, Sink};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use thiserror::Error;
 
-use crate::sound::{metadata::PlaybackMeta, PlaybackExecutable, PrepareCue};
+use crate::{
+    sound::{metadata::PlaybackMeta, PlaybackExecutable, PrepareCue},
+    util::serde::{is_default, string_or_struct::ShortForm},
+};
 
 #[serde_as]
-#[cfg_attr(test, derive(Eq, PartialEq))]
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, Default)]
 pub struct PlaylistCue {
+    #[serde(skip_serializing_if = "Option::is_none")]
     folder: Option<PathBuf>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     files: Vec<PathBuf>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     #[serde(alias = "loop")]
     repeat: bool,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     shuffle: bool,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     volume: Option<u8>,
 
     #[serde_as(as = "Option<serde_with::DurationSecondsWithFrac>")]
+    #[schemars(with = "Option<f64>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     crossfade: Option<Duration>,
 }
 
@@ -66,6 +74,25 @@ impl PlaylistCue {
         self.folder.as_ref().map(AsRef::as_ref)
     }
 
+    /// The listed files followed by everything in the folder, before shuffling
+    pub fn paths(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files = self.files.clone();
+
+        if let Some(folder) = self.folder() {
+            files.extend(
+                folder
+                    .read_dir()?
+                    .filter_map(|entry| Some(entry.ok()?.path())),
+            );
+        }
+
+        Ok(files)
+    }
+
+    pub fn repeat(&self) -> bool {
+        self.repeat
+    }
+
     pub fn with_repeat(mut self) -> Self {
         self.repeat = true;
         self
@@ -75,6 +102,15 @@ impl PlaylistCue {
         self.shuffle = true;
         self
     }
+
+    pub fn volume(&self) -> Option<u8> {
+        self.volume
+    }
+
+    pub fn with_volume(mut self, volume: u8) -> Self {
+        self.volume = Some(volume);
+        self
+    }
 }
 
 impl FromStr for PlaylistCue {
@@ -85,6 +121,38 @@ impl FromStr for PlaylistCue {
     }
 }
 
+impl ShortForm for PlaylistCue {
+    fn short_form(&self) -> Option<String> {
+        let folder = self.folder()?;
+        (*self == Self::from_folder(folder.to_path_buf()))
+            .then(|| folder.to_str().map(String::from))?
+    }
+}
+
+impl Display for PlaylistCue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Playlist")?;
+
+        if let Some(folder) = self.folder() {
+            write!(f, " {}", folder.display())?;
+        }
+        if !self.files.is_empty() {
+            write!(f, " ({} files)", self.files.len())?;
+        }
+        if let Some(vol) = self.volume {
+            write!(f, " at {vol}%")?;
+        }
+        if self.repeat {
+            write!(f, ", looped")?;
+        }
+        if self.shuffle {
+            write!(f, ", shuffled")?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum PlaylistCueError {
     #[error(transparent)]
@@ -100,33 +168,27 @@ impl PrepareCue for PlaylistCue {
     type PrepareError = PlaylistCueError;
 
     fn prepare(&self, label: Option<&str>) -> Result<Self::Executable, Self::PrepareError> {
-        let mut files = self.files.clone();
-
-        if let Some(folder) = self.folder().to_owned() {
-            files.extend(
-                folder
-                    .read_dir()?
-                    .filter_map(|entry| Some(entry.ok()?.path())),
-            );
-        }
+        let mut files = self.paths()?;
 
         if self.shuffle {
             files.shuffle(&mut thread_rng())
         }
 
         let meta = Arc::new(Mutex::new(PlaybackMeta {
-            start: Instant::now(),
-            duration: Duration::ZERO,
+            repeat: self.repeat,
+            ..PlaybackMeta::new(Duration::ZERO)
         }));
 
         let meta_update = meta.clone();
+        let track_count = files.len();
 
         let sources = if self.repeat {
             Either::Left(files.into_iter().cycle())
         } else {
             Either::Right(files.into_iter())
         }
-        .filter_map(move |filename| {
+        .enumerate()
+        .filter_map(move |(i, filename)| {
             let mut file = match std::fs::File::open(&filename) {
                 Err(e) => {
                     log::warn!(
@@ -168,10 +230,14 @@ impl PrepareCue for PlaylistCue {
                 }
             };
 
-            *meta_update.lock().unwrap() = PlaybackMeta {
-                start: Instant::now(),
-                duration,
-            };
+            let mut meta = meta_update.lock().unwrap();
+            meta.start = Instant::now();
+            meta.duration = duration;
+            meta.track = filename
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned());
+            meta.loops = (i / track_count.max(1)) as u32;
+            drop(meta);
 
             log::debug!("Loading playlist file `{}`", filename.display());
 
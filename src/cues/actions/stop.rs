@@ -1,13 +1,16 @@
-use std::{convert::Infallible, str::FromStr};
+use std::{convert::Infallible, fmt::Display, str::FromStr};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-use crate::sound::{ExecuteCue, ExecuteCueError};
+use crate::{
+    sound::{ExecuteCue, ExecuteCueError},
+    util::serde::string_or_struct::ShortForm,
+};
 
 #[serde_as]
-#[cfg_attr(test, derive(Eq, PartialEq))]
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 pub struct StopCue {
     target: String,
 }
@@ -34,6 +37,18 @@ impl FromStr for StopCue {
     }
 }
 
+impl ShortForm for StopCue {
+    fn short_form(&self) -> Option<String> {
+        Some(self.target().to_string())
+    }
+}
+
+impl Display for StopCue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Stop {}", self.target())
+    }
+}
+
 impl ExecuteCue for StopCue {
     fn execute(self, engine: &mut crate::sound::AudioEngine) -> Result<(), ExecuteCueError> {
         if let Some(s) = engine.take_sink(self.target()) {
@@ -1,27 +1,44 @@
-use std::error::Error;
+use std::{
+    error::Error,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use crate::sound::{ExecutableCue, PrepareCue};
 
 use super::actions::*;
+use super::CueNumber;
 
-#[cfg_attr(test, derive(Eq, PartialEq))]
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub struct Cue {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    number: Option<CueNumber>,
+
     label: String,
 
-    #[serde(default)]
+    /// Template the cue was based on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    extends: Option<String>,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     description: String,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     #[serde(alias = "cue")]
     hint: String,
 
     #[serde(flatten)]
+    #[schemars(schema_with = "super::schema::action")]
     action: CueAction,
+
+    /// File the cue was defined in, if it came from an included file
+    #[serde(skip)]
+    source: Option<PathBuf>,
 }
 
 impl Cue {
@@ -29,6 +46,14 @@ impl Cue {
         &self.action
     }
 
+    pub fn number(&self) -> Option<&CueNumber> {
+        self.number.as_ref()
+    }
+
+    pub fn set_number(&mut self, number: Option<CueNumber>) {
+        self.number = number;
+    }
+
     pub fn description(&self) -> &str {
         self.description.as_ref()
     }
@@ -40,19 +65,35 @@ impl Cue {
     pub fn hint(&self) -> &str {
         self.hint.as_ref()
     }
+
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    pub fn set_source(&mut self, source: Option<PathBuf>) {
+        self.source = source;
+    }
 }
 
 #[allow(dead_code)]
 impl Cue {
     pub fn new(label: impl ToString, action: impl Into<CueAction>) -> Self {
         Self {
+            number: None,
             label: label.to_string(),
+            extends: None,
             description: "".to_string(),
             hint: "".to_string(),
             action: action.into(),
+            source: None,
         }
     }
 
+    pub fn with_number(mut self, number: CueNumber) -> Self {
+        self.number = Some(number);
+        self
+    }
+
     pub fn with_description(mut self, desc: impl ToString) -> Self {
         self.description = desc.to_string();
         self
@@ -62,23 +103,36 @@ impl Cue {
         self.hint = context.to_string();
         self
     }
+
+    pub fn with_action(mut self, action: impl Into<CueAction>) -> Self {
+        self.action = action.into();
+        self
+    }
+
+    pub fn with_source(mut self, source: impl Into<PathBuf>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
 }
 
 #[serde_as]
-#[cfg_attr(test, derive(Eq, PartialEq))]
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum CueAction {
-    #[serde(deserialize_with = "crate::util::serde::string_or_struct::deserialize")]
+    #[serde(with = "crate::util::serde::string_or_struct")]
+    #[schemars(schema_with = "crate::util::serde::string_or_struct::schema::<PlaylistCue>")]
     Playlist(PlaylistCue),
 
-    #[serde(deserialize_with = "crate::util::serde::string_or_struct::deserialize")]
+    #[serde(with = "crate::util::serde::string_or_struct")]
+    #[schemars(schema_with = "crate::util::serde::string_or_struct::schema::<PlaybackCue>")]
     Playback(PlaybackCue),
 
-    #[serde(deserialize_with = "crate::util::serde::string_or_struct::deserialize")]
+    #[serde(with = "crate::util::serde::string_or_struct")]
+    #[schemars(schema_with = "crate::util::serde::string_or_struct::schema::<FadeCue>")]
     Fade(FadeCue),
 
-    #[serde(deserialize_with = "crate::util::serde::string_or_struct::deserialize")]
+    #[serde(with = "crate::util::serde::string_or_struct")]
+    #[schemars(schema_with = "crate::util::serde::string_or_struct::schema::<StopCue>")]
     Stop(StopCue),
     // Group(CueGroup),
 }
@@ -107,7 +161,27 @@ impl From<PlaybackCue> for CueAction {
     }
 }
 
+impl Display for CueAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CueAction::Playlist(p) => p.fmt(f),
+            CueAction::Playback(p) => p.fmt(f),
+            CueAction::Fade(c) => c.fmt(f),
+            CueAction::Stop(s) => s.fmt(f),
+        }
+    }
+}
+
 impl CueAction {
+    /// Use `volume` for playback if the cue doesn't set its own
+    pub fn with_default_volume(self, volume: u8) -> Self {
+        match self {
+            CueAction::Playback(p) if p.volume().is_none() => p.with_volume(volume).into(),
+            CueAction::Playlist(p) if p.volume().is_none() => p.with_volume(volume).into(),
+            other => other,
+        }
+    }
+
     pub fn prepare(
         &self,
         label: Option<&str>,
@@ -0,0 +1,93 @@
+use std::fmt::Display;
+
+use super::Script;
+
+/// Labels of the cues that differ between two versions of a script
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ScriptDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl ScriptDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Display for ScriptDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no cues changed");
+        }
+
+        let parts = [
+            ("added", &self.added),
+            ("removed", &self.removed),
+            ("changed", &self.changed),
+        ];
+
+        let mut first = true;
+        for (name, labels) in parts.into_iter().filter(|(_, l)| !l.is_empty()) {
+            if !first {
+                write!(f, "; ")?;
+            }
+            write!(f, "{} {name} ({})", labels.len(), labels.join(", "))?;
+            first = false;
+        }
+
+        Ok(())
+    }
+}
+
+impl Script {
+    pub fn diff(&self, new: &Script) -> ScriptDiff {
+        let mut diff = ScriptDiff::default();
+
+        for cue in new.cuelist() {
+            match self.cuelist().iter().find(|c| c.label() == cue.label()) {
+                None => diff.added.push(cue.label().to_string()),
+                Some(old) if old != cue => diff.changed.push(cue.label().to_string()),
+                Some(_) => {}
+            }
+        }
+
+        for cue in self.cuelist() {
+            if !new.cuelist().iter().any(|c| c.label() == cue.label()) {
+                diff.removed.push(cue.label().to_string());
+            }
+        }
+
+        diff
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cues::{actions::StopCue, Cue, Script};
+
+    #[test]
+    fn diff_by_label() {
+        let old = Script::new(vec![
+            Cue::new("SQ1", StopCue::new("all")),
+            Cue::new("SQ2", StopCue::new("all")),
+            Cue::new("SQ3", StopCue::new("all")),
+        ]);
+        let new = Script::new(vec![
+            Cue::new("SQ1", StopCue::new("all")),
+            Cue::new("SQ2", StopCue::new("SQ1")),
+            Cue::new("SQ4", StopCue::new("all")),
+        ]);
+
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.added, ["SQ4"]);
+        assert_eq!(diff.removed, ["SQ3"]);
+        assert_eq!(diff.changed, ["SQ2"]);
+        assert_eq!(
+            diff.to_string(),
+            "1 added (SQ4); 1 removed (SQ3); 1 changed (SQ2)"
+        );
+    }
+}
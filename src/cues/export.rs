@@ -0,0 +1,313 @@
+use std::{path::Path, time::Duration};
+
+use lofty::AudioFile;
+
+use crate::prelude::*;
+use crate::util::format;
+
+use super::{CueAction, Script};
+
+/// Formats a printable cue sheet can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SheetFormat {
+    Markdown,
+    Html,
+    Csv,
+}
+
+impl SheetFormat {
+    /// Pick the format from the extension of `path`
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("md" | "markdown") => Ok(Self::Markdown),
+            Some("html" | "htm") => Ok(Self::Html),
+            Some("csv") => Ok(Self::Csv),
+            _ => Err(FatalError::UnknownSheetFormat(path.to_path_buf())),
+        }
+    }
+}
+
+/// One line of the cue sheet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetRow {
+    pub section: Option<String>,
+    pub number: String,
+    pub label: String,
+    pub hint: String,
+    pub description: String,
+    pub action: String,
+    pub duration: String,
+    pub targets: String,
+    pub targeted_by: String,
+}
+
+/// Every cue in the script, in cue list order, ready to print
+pub struct CueSheet {
+    title: String,
+    rows: Vec<SheetRow>,
+}
+
+const HEADERS: [&str; 8] = [
+    "#",
+    "Label",
+    "Hint",
+    "Description",
+    "Action",
+    "Duration",
+    "Targets",
+    "Targeted by",
+];
+
+impl CueSheet {
+    pub fn new(title: impl ToString, script: &Script) -> Self {
+        let cues = script.resolved_cuelist();
+
+        let sections = script.cue_sections();
+
+        let rows = cues
+            .iter()
+            .zip(sections)
+            .map(|(cue, section)| {
+                let targeted_by: Vec<_> = cues
+                    .iter()
+                    .filter(|c| target(c.action()) == Some(cue.label()))
+                    .map(|c| format!("{} ({})", c.label(), verb(c.action())))
+                    .collect();
+
+                SheetRow {
+                    section: section.map(|s| s.name().to_string()),
+                    number: cue.number().map(|n| n.to_string()).unwrap_or_default(),
+                    label: cue.label().to_string(),
+                    hint: cue.hint().to_string(),
+                    description: cue.description().to_string(),
+                    action: cue.action().to_string(),
+                    duration: duration(cue.action()),
+                    targets: target(cue.action()).unwrap_or_default().to_string(),
+                    targeted_by: targeted_by.join(", "),
+                }
+            })
+            .collect();
+
+        Self {
+            title: title.to_string(),
+            rows,
+        }
+    }
+
+    pub fn rows(&self) -> &[SheetRow] {
+        &self.rows
+    }
+
+    pub fn render(&self, format: SheetFormat) -> Result<String> {
+        match format {
+            SheetFormat::Markdown => Ok(self.markdown()),
+            SheetFormat::Html => Ok(self.html()),
+            SheetFormat::Csv => self.csv(),
+        }
+    }
+
+    /// The rows split up where a new section starts
+    fn sections(&self) -> Vec<(Option<&str>, &[SheetRow])> {
+        let mut sections: Vec<(Option<&str>, &[SheetRow])> = Vec::new();
+        let mut start = 0;
+
+        for i in 1..=self.rows.len() {
+            if i == self.rows.len() || self.rows[i].section != self.rows[start].section {
+                sections.push((self.rows[start].section.as_deref(), &self.rows[start..i]));
+                start = i;
+            }
+        }
+
+        sections
+    }
+
+    fn markdown(&self) -> String {
+        let cell = |s: &str| s.replace('|', "\\|").replace('\n', " ");
+        let line = |cells: &[&str]| {
+            let cells: Vec<_> = cells.iter().map(|c| cell(c)).collect();
+            format!("| {} |\n", cells.join(" | "))
+        };
+
+        let mut out = format!("# {}\n", self.title);
+
+        for (section, rows) in self.sections() {
+            out.push('\n');
+            if let Some(section) = section {
+                out += &format!("## {section}\n\n");
+            }
+
+            out += &line(&HEADERS);
+            out += &line(&HEADERS.map(|_| "---"));
+            for row in rows {
+                out += &line(&row.cells());
+            }
+        }
+
+        out
+    }
+
+    fn html(&self) -> String {
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        };
+        let line = |tag: &str, cells: &[&str]| {
+            let cells: String = cells
+                .iter()
+                .map(|c| format!("<{tag}>{}</{tag}>", escape(c)))
+                .collect();
+            format!("    <tr>{cells}</tr>\n")
+        };
+
+        let title = escape(&self.title);
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\n{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+
+        for (section, rows) in self.sections() {
+            if let Some(section) = section {
+                out += &format!("<h2>{}</h2>\n", escape(section));
+            }
+
+            out += "<table>\n  <thead>\n";
+            out += &line("th", &HEADERS);
+            out += "  </thead>\n  <tbody>\n";
+            for row in rows {
+                out += &line("td", &row.cells());
+            }
+            out += "  </tbody>\n</table>\n";
+        }
+
+        out + "</body>\n</html>\n"
+    }
+
+    fn csv(&self) -> Result<String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        writer.write_record(std::iter::once("Section").chain(HEADERS))?;
+        for row in &self.rows {
+            let section = row.section.as_deref().unwrap_or_default();
+            writer.write_record(std::iter::once(section).chain(row.cells()))?;
+        }
+
+        let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+impl SheetRow {
+    fn cells(&self) -> [&str; 8] {
+        [
+            &self.number,
+            &self.label,
+            &self.hint,
+            &self.description,
+            &self.action,
+            &self.duration,
+            &self.targets,
+            &self.targeted_by,
+        ]
+    }
+}
+
+/// Keeps the tables readable when printed
+const STYLE: &str = "\
+body { font-family: sans-serif; font-size: 10pt; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1em; }
+th, td { border: 1px solid #888; padding: 0.2em 0.4em; text-align: left; vertical-align: top; }
+thead { display: table-header-group; }
+tr { page-break-inside: avoid; }
+";
+
+/// The cue another cue acts on
+fn target(action: &CueAction) -> Option<&str> {
+    match action {
+        CueAction::Fade(f) => Some(f.target()),
+        CueAction::Stop(s) => Some(s.target()),
+        CueAction::Playback(_) | CueAction::Playlist(_) => None,
+    }
+}
+
+fn verb(action: &CueAction) -> &'static str {
+    match action {
+        CueAction::Fade(_) => "fade",
+        CueAction::Stop(_) => "stop",
+        CueAction::Playback(_) | CueAction::Playlist(_) => "play",
+    }
+}
+
+/// How long the cue runs for, using the length of its audio files where
+/// the cue doesn't say. `?` if a file couldn't be read.
+fn duration(action: &CueAction) -> String {
+    let length = |path: &Path| -> Option<Duration> {
+        Some(lofty::read_from_path(path).ok()?.properties().duration())
+    };
+    let looped = |d: String, repeat: bool| if repeat { format!("{d}, looped") } else { d };
+
+    match action {
+        CueAction::Playback(p) => {
+            let d = p.duration().or_else(|| length(p.file()));
+            looped(d.map_or("?".to_string(), format::mm_ss), p.repeat())
+        }
+        CueAction::Playlist(p) => {
+            let total = p
+                .paths()
+                .ok()
+                .and_then(|paths| paths.iter().map(|f| length(f)).sum::<Option<Duration>>());
+            looped(total.map_or("?".to_string(), format::mm_ss), p.repeat())
+        }
+        CueAction::Fade(f) => format::secs(*f.duration()),
+        CueAction::Stop(_) => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::cues::{
+        actions::{FadeCue, PlaybackCue, StopCue},
+        Cue, CueNumber, Section,
+    };
+
+    #[test]
+    fn cue_sheet() {
+        let script = Script::new(vec![
+            Cue::new(
+                "SQ1",
+                PlaybackCue::new("missing.wav").for_duration(Duration::from_secs(75)),
+            )
+            .with_number(CueNumber::whole(1))
+            .with_hint("pg. 1 | lights up"),
+            Cue::new(
+                "SQ2",
+                FadeCue::new("SQ1").for_duration(Duration::from_secs(3)),
+            ),
+            Cue::new("SQ3", StopCue::new("SQ1")).with_description("<Blackout>"),
+        ])
+        .with_sections(vec![Section::new("Act 2", "SQ2")]);
+
+        let sheet = CueSheet::new("Show", &script);
+        let rows = sheet.rows();
+        assert_eq!(rows[0].duration, "01:15");
+        assert_eq!(rows[0].targeted_by, "SQ2 (fade), SQ3 (stop)");
+        assert_eq!(rows[1].duration, "3s");
+        assert_eq!(rows[2].targets, "SQ1");
+        assert_eq!(rows[2].section.as_deref(), Some("Act 2"));
+
+        let markdown = sheet.render(SheetFormat::Markdown).unwrap();
+        assert!(markdown.contains("| 1 | SQ1 | pg. 1 \\| lights up |"));
+        assert!(markdown.contains("\n## Act 2\n"));
+
+        let html = sheet.render(SheetFormat::Html).unwrap();
+        assert!(html.contains("<td>&lt;Blackout&gt;</td>"));
+
+        let csv = sheet.render(SheetFormat::Csv).unwrap();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.starts_with("Section,#,Label,"));
+    }
+}
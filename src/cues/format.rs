@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use serde_yaml::Value;
+
+use crate::prelude::*;
+
+/// File formats a script can be written in, picked by file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl ScriptFormat {
+    pub const EXTENSIONS: [(&'static str, ScriptFormat); 4] = [
+        ("yaml", ScriptFormat::Yaml),
+        ("yml", ScriptFormat::Yaml),
+        ("json", ScriptFormat::Json),
+        ("toml", ScriptFormat::Toml),
+    ];
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+
+        Self::EXTENSIONS
+            .into_iter()
+            .find(|(e, _)| e.eq_ignore_ascii_case(ext))
+            .map(|(_, format)| format)
+            .ok_or_else(|| FatalError::UnknownFormat(path.to_path_buf()))
+    }
+
+    /// Parse `text` into a YAML value, so everything after this works the
+    /// same no matter what format the script was written in
+    pub fn parse(&self, text: &str) -> Result<Value> {
+        Ok(match self {
+            ScriptFormat::Yaml => serde_yaml::from_str(text)?,
+            ScriptFormat::Json => serde_json::from_str(text)?,
+            ScriptFormat::Toml => toml::from_str(text)?,
+        })
+    }
+
+    pub fn write(&self, value: &Value) -> Result<String> {
+        Ok(match self {
+            ScriptFormat::Yaml => serde_yaml::to_string(value)?,
+            ScriptFormat::Json => serde_json::to_string_pretty(value)? + "\n",
+            ScriptFormat::Toml => toml::to_string_pretty(value)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ScriptFormat;
+
+    #[test]
+    fn same_value_in_every_format() {
+        let yaml =
+            "master: 80\ncuelist:\n- label: SQ1\n  fade:\n    target: all\n    duration: 2.5\n";
+        let value = ScriptFormat::Yaml.parse(yaml).unwrap();
+
+        for format in [ScriptFormat::Json, ScriptFormat::Toml] {
+            let text = format.write(&value).unwrap();
+            assert_eq!(format.parse(&text).unwrap(), value, "{text}");
+        }
+    }
+}
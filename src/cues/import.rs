@@ -0,0 +1,364 @@
+use std::{
+    collections::HashSet,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+use crate::prelude::*;
+
+use super::{
+    actions::{FadeCue, PlaybackCue},
+    Cue, CueAction, CueNumber, Script,
+};
+
+/// Which spreadsheet columns hold which cue fields, read from a mapping file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CsvMapping {
+    columns: Columns,
+
+    /// Put in front of the cue number to label rows that don't have a label
+    label_prefix: String,
+}
+
+/// Header of the column holding each field, or `None` to leave it out
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Columns {
+    number: Option<String>,
+    label: Option<String>,
+    description: Option<String>,
+    hint: Option<String>,
+    page: Option<String>,
+    file: Option<String>,
+    volume: Option<String>,
+    fade: Option<String>,
+    target: Option<String>,
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        Self {
+            columns: Columns::default(),
+            label_prefix: "Q".to_string(),
+        }
+    }
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        let column = |name: &str| Some(name.to_string());
+
+        Self {
+            number: column("Cue"),
+            label: column("Label"),
+            description: column("Description"),
+            hint: column("Hint"),
+            page: column("Page"),
+            file: column("File"),
+            volume: column("Volume"),
+            fade: column("Fade"),
+            target: column("Target"),
+        }
+    }
+}
+
+/// Why a row of the spreadsheet didn't become a cue
+#[derive(Debug, thiserror::Error)]
+pub enum RowError {
+    #[error("no label or cue number")]
+    NoLabel,
+
+    #[error("no file or fade")]
+    NoAction,
+
+    #[error("fade has no target and no earlier sound to fade")]
+    NoFadeTarget,
+
+    #[error("fade target {0} isn't an earlier cue")]
+    FadeTarget(String),
+
+    #[error("label {0} is already used")]
+    DuplicateLabel(String),
+
+    #[error("cue number {0} doesn't come after {1}")]
+    NumberOrder(CueNumber, CueNumber),
+
+    #[error("{} doesn't exist", .0.display())]
+    MissingFile(PathBuf),
+
+    #[error("`{0}` is not a volume, expected a percentage like 80")]
+    Volume(String),
+
+    #[error("`{0}` is not a fade time, expected a number of seconds")]
+    Fade(String),
+
+    #[error(transparent)]
+    Number(#[from] super::number::ParseCueNumberError),
+}
+
+/// The result of an import: a valid script, and the rows that were left out of it
+pub struct CsvImport {
+    pub script: Script,
+
+    /// Row number in the spreadsheet, counting the header as row 1, and what was wrong with it
+    pub skipped: Vec<(usize, RowError)>,
+
+    /// Mapped columns that aren't in the spreadsheet
+    pub missing: Vec<String>,
+}
+
+/// Column index of each field
+struct Indices {
+    number: Option<usize>,
+    label: Option<usize>,
+    description: Option<usize>,
+    hint: Option<usize>,
+    page: Option<usize>,
+    file: Option<usize>,
+    volume: Option<usize>,
+    fade: Option<usize>,
+    target: Option<usize>,
+}
+
+impl CsvMapping {
+    /// Turn every row of a cue sheet into a cue. Rows with a file become
+    /// playback cues, using the fade time to fade in; rows with only a fade
+    /// time fade out their target, or the last sound before them. Files are
+    /// looked for relative to `dir`, the folder the script is saved in.
+    pub fn import(&self, input: impl io::Read, dir: &Path) -> Result<CsvImport> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(input);
+        let headers = reader.headers()?.clone();
+
+        let mut missing = Vec::new();
+        let mut index = |column: &Option<String>| {
+            let column = column.as_deref()?;
+            let i = headers
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(column.trim()));
+            if i.is_none() {
+                missing.push(column.to_string());
+            }
+            i
+        };
+
+        let c = &self.columns;
+        let indices = Indices {
+            number: index(&c.number),
+            label: index(&c.label),
+            description: index(&c.description),
+            hint: index(&c.hint),
+            page: index(&c.page),
+            file: index(&c.file),
+            volume: index(&c.volume),
+            fade: index(&c.fade),
+            target: index(&c.target),
+        };
+
+        let mut cues: Vec<Cue> = Vec::new();
+        let mut labels = HashSet::new();
+        let mut skipped = Vec::new();
+        let mut last_sound = None;
+
+        for record in reader.records() {
+            let record = record?;
+            let row = record.position().map_or(0, |p| p.line() as usize);
+
+            if record.iter().all(|f| f.trim().is_empty()) {
+                continue;
+            }
+
+            let last_number = cues.iter().rev().find_map(Cue::number);
+            let result = self
+                .cue(&indices, &record, last_sound.as_deref())
+                .and_then(|cue| check(cue, &labels, last_number, dir));
+
+            match result {
+                Ok(cue) => {
+                    if let CueAction::Playback(_) = cue.action() {
+                        last_sound = Some(cue.label().to_string());
+                    }
+                    labels.insert(cue.label().to_string());
+                    cues.push(cue);
+                }
+                Err(e) => skipped.push((row, e)),
+            }
+        }
+
+        // Every row was checked against the ones before it, so this only
+        // catches mistakes in the checks. Files were found relative to `dir`.
+        let script = Script::new(cues).validate_without_files()?;
+
+        Ok(CsvImport {
+            script,
+            skipped,
+            missing,
+        })
+    }
+
+    fn cue(
+        &self,
+        indices: &Indices,
+        record: &csv::StringRecord,
+        last_sound: Option<&str>,
+    ) -> std::result::Result<Cue, RowError> {
+        let get = |i: Option<usize>| {
+            i.and_then(|i| record.get(i))
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+        };
+
+        let number = get(indices.number)
+            .map(str::parse::<CueNumber>)
+            .transpose()?;
+        let label = match (get(indices.label), &number) {
+            (Some(label), _) => label.to_string(),
+            (None, Some(n)) => format!("{}{n}", self.label_prefix),
+            (None, None) => return Err(RowError::NoLabel),
+        };
+
+        let volume = get(indices.volume)
+            .map(|v| {
+                v.trim_end_matches('%')
+                    .trim()
+                    .parse::<u8>()
+                    .map_err(|_| RowError::Volume(v.to_string()))
+            })
+            .transpose()?;
+
+        let fade = get(indices.fade)
+            .map(|f| {
+                f.trim_end_matches('s')
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                    .ok_or_else(|| RowError::Fade(f.to_string()))
+            })
+            .transpose()?;
+
+        let action: CueAction = match (get(indices.file), fade) {
+            (Some(file), fade) => {
+                let mut playback = PlaybackCue::new(PathBuf::from(file));
+                if let Some(volume) = volume {
+                    playback = playback.with_volume(volume);
+                }
+                if let Some(fade) = fade {
+                    playback = playback.fade_in_for(fade);
+                }
+                playback.into()
+            }
+            (None, Some(fade)) => {
+                let target = get(indices.target)
+                    .or(last_sound)
+                    .ok_or(RowError::NoFadeTarget)?;
+                FadeCue::new(target)
+                    .to_volume(volume.unwrap_or(0))
+                    .for_duration(fade)
+                    .into()
+            }
+            (None, None) => return Err(RowError::NoAction),
+        };
+
+        let hint = match (get(indices.page), get(indices.hint)) {
+            (Some(page), Some(hint)) => format!("pg. {page}: {hint}"),
+            (Some(page), None) => format!("pg. {page}"),
+            (None, hint) => hint.unwrap_or_default().to_string(),
+        };
+
+        let mut cue = Cue::new(label, action)
+            .with_description(get(indices.description).unwrap_or_default())
+            .with_hint(hint);
+        cue.set_number(number);
+
+        Ok(cue)
+    }
+}
+
+/// Check `cue` against the cues imported before it, which is what
+/// validating the script would find wrong with it
+fn check(
+    cue: Cue,
+    labels: &HashSet<String>,
+    last_number: Option<&CueNumber>,
+    dir: &Path,
+) -> std::result::Result<Cue, RowError> {
+    if labels.contains(cue.label()) {
+        return Err(RowError::DuplicateLabel(cue.label().to_string()));
+    }
+
+    if let (Some(number), Some(last)) = (cue.number(), last_number) {
+        if number <= last {
+            return Err(RowError::NumberOrder(number.clone(), last.clone()));
+        }
+    }
+
+    match cue.action() {
+        CueAction::Playback(p) if !dir.join(p.file()).exists() => {
+            return Err(RowError::MissingFile(p.file().clone()));
+        }
+        CueAction::Fade(f) if f.target() != "all" && !labels.contains(f.target()) => {
+            return Err(RowError::FadeTarget(f.target().to_string()));
+        }
+        _ => {}
+    }
+
+    Ok(cue)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+    use crate::util::fs::TempDir;
+
+    #[test]
+    fn import_cue_sheet() {
+        let dir = TempDir::new("import");
+        fs::write(dir.join("preshow.wav"), "").unwrap();
+
+        // Files are relative to the script, and rows are numbered by line
+        // even when a quoted field spans several
+        let csv = "Cue,Page,Description,File,Volume,Fade\n\
+                   1,1,\"Preshow\nmusic\",preshow.wav,80%,2\n\
+                   ,,,,,\n\
+                   2,3,Music out,,,5s\n\
+                   2,4,Same number,,,1\n\
+                   3,5,Missing sound,missing.wav,,\n\
+                   4,,Nothing to do,,,\n";
+
+        let import = CsvMapping::default()
+            .import(csv.as_bytes(), dir.path())
+            .unwrap();
+
+        let cues = import.script.cuelist();
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].label(), "Q1");
+        assert_eq!(cues[0].hint(), "pg. 1");
+        assert_eq!(cues[0].description(), "Preshow\nmusic");
+        assert_eq!(
+            cues[0].action(),
+            &PlaybackCue::new("preshow.wav")
+                .with_volume(80)
+                .fade_in_for(Duration::from_secs(2))
+                .into()
+        );
+        assert_eq!(
+            cues[1].action(),
+            &FadeCue::new("Q1")
+                .for_duration(Duration::from_secs(5))
+                .into()
+        );
+
+        let skipped: Vec<_> = import.skipped.iter().map(|(row, _)| *row).collect();
+        assert_eq!(skipped, [6, 7, 8]);
+        assert!(matches!(&import.skipped[0].1, RowError::DuplicateLabel(l) if l == "Q2"));
+        assert!(matches!(import.skipped[1].1, RowError::MissingFile(_)));
+        assert!(matches!(import.skipped[2].1, RowError::NoAction));
+        assert_eq!(import.missing, ["Label", "Hint", "Target"]);
+    }
+}
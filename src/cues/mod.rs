@@ -1,11 +1,29 @@
 mod cue;
+mod diff;
+mod export;
+mod format;
+mod import;
+mod number;
+mod schema;
 mod script;
+mod section;
+mod settings;
+mod template;
 mod validation;
 
 pub mod actions;
 
-pub use cue::Cue;
+pub use cue::{Cue, CueAction};
+pub use diff::ScriptDiff;
+pub use export::{CueSheet, SheetFormat};
+pub use format::ScriptFormat;
+pub use import::CsvMapping;
+pub use number::CueNumber;
+pub use schema::script_schema;
 pub use script::Script;
+pub use section::Section;
+pub use settings::{CacheSettings, Defaults, GoSettings, LogSettings, Schedule};
+pub use template::Expander;
 
 #[cfg(test)]
 mod test;
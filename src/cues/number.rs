@@ -0,0 +1,239 @@
+use std::{fmt::Display, str::FromStr};
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{
+        InstanceType, NumberValidation, Schema, SchemaObject, StringValidation, SubschemaValidation,
+    },
+    JsonSchema,
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Decimal cue number like `12` or `12.5`, used to slot new cues between
+/// existing ones without renumbering the whole show
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CueNumber {
+    whole: u32,
+    /// Digits after the point, without trailing zeros. Comparing these as
+    /// strings orders them the same as comparing them as decimals.
+    fraction: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("`{0}` is not a cue number, expected something like 12 or 12.5")]
+pub struct ParseCueNumberError(String);
+
+/// Finest step used when picking a number between two others
+const MAX_DIGITS: u32 = 6;
+
+impl CueNumber {
+    pub fn whole(n: u32) -> Self {
+        Self {
+            whole: n,
+            fraction: String::new(),
+        }
+    }
+
+    /// This number scaled up by `10^digits`, rounded down or up
+    fn scaled(&self, digits: u32, round_up: bool) -> u64 {
+        let mut scaled = u64::from(self.whole) * 10u64.pow(digits);
+        let mut truncated = false;
+
+        for (i, d) in self.fraction.bytes().enumerate() {
+            let d = u64::from(d - b'0');
+            match digits.checked_sub(i as u32 + 1) {
+                Some(exp) => scaled += d * 10u64.pow(exp),
+                None => truncated |= d > 0,
+            }
+        }
+
+        scaled + u64::from(round_up && truncated)
+    }
+
+    fn from_scaled(scaled: u64, digits: u32) -> Option<Self> {
+        let divisor = 10u64.pow(digits);
+        let fraction = format!("{:0width$}", scaled % divisor, width = digits as usize);
+
+        Some(Self {
+            whole: (scaled / divisor).try_into().ok()?,
+            fraction: fraction.trim_end_matches('0').to_string(),
+        })
+    }
+
+    /// The simplest number strictly between `before` and `after`, preferring
+    /// whole numbers and then points halfway between, like 12.5
+    pub fn between(before: Option<&Self>, after: Option<&Self>) -> Option<Self> {
+        let Some(after) = after else {
+            // There's nothing after the highest whole number
+            return before
+                .map_or(Some(1), |b| b.whole.checked_add(1))
+                .map(Self::whole);
+        };
+
+        (0..=MAX_DIGITS).find_map(|digits| {
+            let lo = before.map_or(0, |b| b.scaled(digits, false));
+            let hi = after.scaled(digits, true);
+
+            if hi < lo + 2 {
+                return None;
+            }
+
+            // Whole numbers first, then the midpoint
+            let mid = if digits == 0 { lo + 1 } else { (lo + hi) / 2 };
+            Self::from_scaled(mid, digits)
+        })
+    }
+}
+
+impl FromStr for CueNumber {
+    type Err = ParseCueNumberError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseCueNumberError(s.to_string());
+        let (whole, fraction) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(err());
+        }
+
+        Ok(Self {
+            whole: whole.parse().map_err(|_| err())?,
+            fraction: fraction.trim_end_matches('0').to_string(),
+        })
+    }
+}
+
+impl Display for CueNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.fraction.is_empty() {
+            write!(f, "{}", self.whole)
+        } else {
+            write!(f, "{}.{}", self.whole, self.fraction)
+        }
+    }
+}
+
+impl Serialize for CueNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.fraction.is_empty() {
+            serializer.serialize_u32(self.whole)
+        } else {
+            // Written as a string so no digits are lost to floating point
+            serializer.collect_str(self)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CueNumber {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NumberVisitor;
+
+        impl<'de> de::Visitor<'de> for NumberVisitor {
+            type Value = CueNumber;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a cue number like 12 or 12.5")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(NumberVisitor)
+    }
+}
+
+/// A number like `12` or `12.5`, which can also be written as a string
+impl JsonSchema for CueNumber {
+    fn schema_name() -> String {
+        "CueNumber".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let number = SchemaObject {
+            instance_type: Some(InstanceType::Number.into()),
+            number: Some(Box::new(NumberValidation {
+                minimum: Some(0.0),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let string = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(r"^\s*[0-9]+(\.[0-9]*)?\s*$".to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![number.into(), string.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CueNumber;
+
+    fn n(s: &str) -> CueNumber {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn decimal_order() {
+        assert!(n("12") < n("12.45"));
+        assert!(n("12.45") < n("12.5"));
+        assert!(n("12.5") < n("12.55"));
+        assert!(n("12.9") < n("13"));
+        assert_eq!(n("12.50"), n("12.5"));
+        assert!("12.a".parse::<CueNumber>().is_err());
+    }
+
+    #[test]
+    fn number_between() {
+        let between = |a: Option<&str>, b: Option<&str>| {
+            CueNumber::between(a.map(n).as_ref(), b.map(n).as_ref()).map(|c| c.to_string())
+        };
+
+        assert_eq!(between(Some("12"), None).as_deref(), Some("13"));
+        assert_eq!(between(Some("12"), Some("15")).as_deref(), Some("13"));
+        assert_eq!(between(Some("12"), Some("13")).as_deref(), Some("12.5"));
+        assert_eq!(between(Some("12.5"), Some("13")).as_deref(), Some("12.7"));
+        assert_eq!(
+            between(Some("12.5"), Some("12.6")).as_deref(),
+            Some("12.55")
+        );
+        assert_eq!(between(None, Some("1")).as_deref(), Some("0.5"));
+        assert_eq!(between(Some("4294967295"), None), None);
+    }
+
+    #[test]
+    fn serialize_exactly() {
+        let number = n("12.100000000000000001");
+        let json = serde_json::to_string(&number).unwrap();
+
+        assert_eq!(json, r#""12.100000000000000001""#);
+        assert_eq!(serde_json::from_str::<CueNumber>(&json).unwrap(), number);
+        assert_eq!(serde_json::to_string(&n("12")).unwrap(), "12");
+    }
+}
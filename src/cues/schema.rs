@@ -0,0 +1,102 @@
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{
+        ArrayValidation, InstanceType, ObjectValidation, RootSchema, Schema, SchemaObject,
+        SubschemaValidation,
+    },
+    schema_for, JsonSchema,
+};
+
+use super::{Cue, CueAction, Script};
+
+/// JSON Schema for script files, for completion and linting in editors.
+/// Included files only use part of it, so they match it too.
+pub fn script_schema() -> RootSchema {
+    schema_for!(Script)
+}
+
+fn object(properties: ObjectValidation) -> SchemaObject {
+    SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(properties)),
+        ..Default::default()
+    }
+}
+
+/// `cuelist`: cues that `extends` a template only need a label, since the
+/// rest can come from the template
+pub(super) fn cuelist(gen: &mut SchemaGenerator) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(cue(gen).into()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+fn cue(gen: &mut SchemaGenerator) -> Schema {
+    let extended = object(ObjectValidation {
+        required: ["label", "extends"].map(String::from).into(),
+        ..Default::default()
+    });
+
+    SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            any_of: Some(vec![gen.subschema_for::<Cue>(), extended.into()]),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// The action of a cue. It's flattened into the cue, so unlike a plain enum
+/// each variant has to allow the cue's other fields next to it.
+pub(super) fn action(gen: &mut SchemaGenerator) -> Schema {
+    let mut schema = CueAction::json_schema(gen).into_object();
+
+    let variants = schema
+        .subschemas
+        .iter_mut()
+        .flat_map(|s| s.one_of.iter_mut().flatten());
+    for variant in variants {
+        if let Schema::Object(SchemaObject {
+            object: Some(object),
+            ..
+        }) = variant
+        {
+            object.additional_properties = None;
+        }
+    }
+
+    schema.into()
+}
+
+/// `templates`: partial cues by name, which aren't checked until a cue uses them
+pub(super) fn templates(_: &mut SchemaGenerator) -> Schema {
+    object(ObjectValidation {
+        additional_properties: Some(Box::new(object(Default::default()).into())),
+        ..Default::default()
+    })
+    .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::script_schema;
+
+    /// `script.schema.json` is what editors point at, so it has to match the types.
+    /// Update it with `encue schema script.schema.json`.
+    #[test]
+    fn schema_file_in_sync() {
+        let schema = serde_json::to_string_pretty(&script_schema()).unwrap();
+        assert_eq!(
+            include_str!("../../script.schema.json").trim_end(),
+            schema,
+            "script.schema.json is out of date"
+        );
+    }
+}
@@ -1,20 +1,76 @@
-use std::{fs, io};
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
 
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
 
 use crate::prelude::*;
-use crate::util::defaults;
+use crate::util::{defaults, fs::write_atomic, serde::is_default};
 
-use super::Cue;
+use super::{
+    CacheSettings, Cue, CueNumber, Defaults, Expander, GoSettings, LogSettings, Schedule,
+    ScriptFormat, Section,
+};
 
 #[cfg_attr(test, derive(Eq, PartialEq))]
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub struct Script {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    include: Vec<PathBuf>,
+
+    #[serde(default)]
+    #[schemars(schema_with = "super::schema::cuelist")]
     cuelist: Vec<Cue>,
 
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    sections: Vec<Section>,
+
     #[serde(default = "defaults::default_vol")]
     master: u8,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    defaults: Defaults,
+
+    /// Partial cues that other cues can `extends`. They're applied
+    /// before the cues are parsed, so they're kept as raw YAML.
+    #[serde(default, skip_serializing_if = "Mapping::is_empty")]
+    #[schemars(schema_with = "super::schema::templates")]
+    templates: Mapping,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    go: GoSettings,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    schedule: Schedule,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    cache: CacheSettings,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    logs: LogSettings,
+
+    /// Every file pulled in by `include`, in the order they were loaded
+    #[serde(skip)]
+    files: Vec<PathBuf>,
+}
+
+/// An included file, which can only hold cues and further includes
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase", deny_unknown_fields)]
+struct ScriptPart {
+    #[serde(default)]
+    include: Vec<PathBuf>,
+
+    #[serde(default)]
+    cuelist: Vec<Cue>,
+
+    #[serde(default)]
+    sections: Vec<Section>,
 }
 
 #[allow(dead_code)]
@@ -23,10 +79,43 @@ impl Script {
         self.cuelist.as_ref()
     }
 
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
     pub fn master(&self) -> u8 {
         self.master
     }
 
+    pub fn go(&self) -> &GoSettings {
+        &self.go
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn cache(&self) -> &CacheSettings {
+        &self.cache
+    }
+
+    pub fn logs(&self) -> &LogSettings {
+        &self.logs
+    }
+
+    pub fn defaults(&self) -> &Defaults {
+        &self.defaults
+    }
+
+    /// Applies this script's templates and defaults to cues
+    pub fn expander(&self) -> Result<Expander> {
+        Expander::new(&self.defaults, &self.templates)
+    }
+
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
     pub fn cue_names(&self) -> Vec<&str> {
         self.cuelist().iter().map(Cue::label).collect()
     }
@@ -34,17 +123,151 @@ impl Script {
 
 #[allow(dead_code)]
 impl Script {
+    /// `script.yaml` in the working directory, or `script.json` or `script.toml`
+    /// if that's what there is
+    pub fn default_path() -> PathBuf {
+        ScriptFormat::EXTENSIONS
+            .into_iter()
+            .map(|(ext, _)| PathBuf::from("script").with_extension(ext))
+            .find(|p| p.exists())
+            .unwrap_or_else(|| PathBuf::from("script.yaml"))
+    }
+
+    /// The script file at `path` as it's written, without loading includes
+    /// or applying templates
+    pub fn read_raw(path: impl AsRef<Path>) -> Result<Value> {
+        read(path.as_ref())
+    }
+
+    pub fn write_raw(path: impl AsRef<Path>, value: &Value) -> Result<()> {
+        write(path.as_ref(), value)
+    }
+
     pub fn load() -> Result<Self> {
-        let f = fs::File::open("script.yaml")?;
-        let yaml = io::read_to_string(f)?;
+        Self::load_from(Self::default_path())
+    }
+
+    /// Load the script at `path` along with everything it includes
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let value = read(path)?;
+        let expander = in_file(path, || Expander::from_root(&value))?;
+        let mut script: Self = parse(path, value, &expander)?;
+
+        for cue in &mut script.cuelist {
+            cue.set_source(Some(path.to_path_buf()));
+        }
+        for section in &mut script.sections {
+            section.set_source(Some(path.to_path_buf()));
+        }
+
+        let mut stack = vec![path.to_path_buf()];
+        for include in script.include.clone() {
+            script.load_include(&relative_to(path, &include), &mut stack, &expander)?;
+        }
+
+        Ok(script)
+    }
+
+    /// Append the cues from `path` and its own includes, depth first
+    fn load_include(
+        &mut self,
+        path: &Path,
+        stack: &mut Vec<PathBuf>,
+        expander: &Expander,
+    ) -> Result<()> {
+        if stack.iter().any(|p| same_file(p, path)) {
+            return Err(FatalError::IncludeCycle(path.to_path_buf()));
+        }
+
+        let part: ScriptPart = parse(path, read(path)?, expander)?;
+        log::debug!(
+            "Included {} cues from {}",
+            part.cuelist.len(),
+            path.display()
+        );
+
+        self.files.push(path.to_path_buf());
+        self.cuelist
+            .extend(part.cuelist.into_iter().map(|c| c.with_source(path)));
+        self.sections.extend(part.sections.into_iter().map(|mut s| {
+            s.set_source(Some(path.to_path_buf()));
+            s
+        }));
+
+        stack.push(path.to_path_buf());
+        for include in &part.include {
+            self.load_include(&relative_to(path, include), stack, expander)?;
+        }
+        stack.pop();
 
-        Ok(serde_yaml::from_str(&yaml)?)
+        Ok(())
+    }
+
+    /// Write the script back to `path`, and each included file's cues back to that file
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<()> {
+        let included =
+            |source: Option<&Path>| source.is_some_and(|s| self.files.iter().any(|f| f == s));
+
+        let expander = self.expander()?;
+        let compact = |file: Option<&Path>| -> Result<Value> {
+            let cues = self
+                .cuelist
+                .iter()
+                .filter(|c| match file {
+                    Some(file) => c.source() == Some(file),
+                    None => !included(c.source()),
+                })
+                .map(|c| expander.compact_cue(c))
+                .collect::<Result<_>>()?;
+
+            Ok(Value::Sequence(cues))
+        };
+
+        let mut root = self.clone();
+        root.sections.retain(|s| !included(s.source()));
+        let mut value = serde_yaml::to_value(&root)?;
+        value["cuelist"] = compact(None)?;
+        write(path.as_ref(), &value)?;
+
+        for file in &self.files {
+            // Keep everything else in the file, like its own includes
+            let mut part = match read(file) {
+                Ok(Value::Mapping(m)) => m,
+                _ => Default::default(),
+            };
+            part.insert("cuelist".into(), compact(Some(file))?);
+
+            let sections: Vec<_> = self
+                .sections
+                .iter()
+                .filter(|s| s.source() == Some(file))
+                .collect();
+            if sections.is_empty() {
+                part.remove("sections");
+            } else {
+                part.insert("sections".into(), serde_yaml::to_value(sections)?);
+            }
+
+            write(file, &Value::Mapping(part))?;
+        }
+
+        Ok(())
     }
 
     pub fn new(cues: Vec<Cue>) -> Self {
         Self {
+            include: Vec::new(),
             cuelist: cues,
+            sections: Vec::new(),
             master: 100,
+            defaults: Defaults::default(),
+            templates: Mapping::new(),
+            go: GoSettings::default(),
+            schedule: Schedule::default(),
+            cache: CacheSettings::default(),
+            logs: LogSettings::default(),
+            files: Vec::new(),
         }
     }
 
@@ -52,4 +275,108 @@ impl Script {
         self.master = master;
         self
     }
+
+    /// Put the cues in order of their numbers. Unnumbered cues stay
+    /// after the numbered cue they follow. Cues are only sorted among the
+    /// others in the same section and file, and the cue a section starts
+    /// at stays first in it.
+    pub fn sort_by_number(mut self) -> Self {
+        let starts: HashSet<_> = self.section_starts().into_iter().map(|(i, _)| i).collect();
+
+        let mut block = 0;
+        let mut source = None;
+        let mut key = None;
+        let mut keyed = Vec::with_capacity(self.cuelist.len());
+
+        for (i, cue) in std::mem::take(&mut self.cuelist).into_iter().enumerate() {
+            let start = starts.contains(&i);
+            if i > 0 && (start || cue.source() != source.as_deref()) {
+                block += 1;
+                key = None;
+            }
+            source = cue.source().map(Path::to_path_buf);
+
+            if let Some(n) = cue.number() {
+                key = Some(n.clone());
+            }
+            keyed.push(((block, !start, key.clone()), cue));
+        }
+
+        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.cuelist = keyed.into_iter().map(|(_, cue)| cue).collect();
+        self
+    }
+
+    /// Give every cue a whole number, counting up from `start` by `step`
+    pub fn renumber(mut self, start: u32, step: u32) -> Self {
+        for (i, cue) in self.cuelist.iter_mut().enumerate() {
+            let n = start.saturating_add((i as u32).saturating_mul(step));
+            cue.set_number(Some(CueNumber::whole(n)));
+        }
+        self
+    }
+
+    pub fn with_sections(mut self, sections: Vec<Section>) -> Self {
+        self.sections = sections;
+        self
+    }
+
+    pub fn with_cuelist(mut self, cues: Vec<Cue>) -> Self {
+        self.cuelist = cues;
+        self
+    }
+
+    pub fn with_defaults(mut self, defaults: Defaults) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    pub fn with_go(mut self, go: GoSettings) -> Self {
+        self.go = go;
+        self
+    }
+
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+}
+
+/// Run `f`, naming `path` in any error it returns
+fn in_file<T>(path: &Path, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    f().map_err(|e| FatalError::InFile(path.to_path_buf(), Box::new(e)))
+}
+
+fn read(path: &Path) -> Result<Value> {
+    in_file(path, || {
+        let format = ScriptFormat::from_path(path)?;
+        format.parse(&io::read_to_string(fs::File::open(path)?)?)
+    })
+}
+
+fn write(path: &Path, value: &Value) -> Result<()> {
+    in_file(path, || {
+        let text = ScriptFormat::from_path(path)?.write(value)?;
+        Ok(write_atomic(path, text)?)
+    })
+}
+
+/// Apply templates and defaults to the cues in `value` and parse it
+fn parse<T: DeserializeOwned>(path: &Path, mut value: Value, expander: &Expander) -> Result<T> {
+    in_file(path, || {
+        expander.expand_file(&mut value)?;
+        Ok(serde_yaml::from_value(value)?)
+    })
+}
+
+/// Resolve `include` relative to the directory of the file that includes it
+fn relative_to(file: &Path, include: &Path) -> PathBuf {
+    file.parent().unwrap_or(Path::new("")).join(include)
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
 }
@@ -0,0 +1,140 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{Cue, Script};
+
+/// A named part of the show, like an act or scene, starting at a cue and
+/// running until the next section starts
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub struct Section {
+    name: String,
+
+    /// Label of the first cue in the section
+    start: String,
+
+    /// Volume for playback cues in the section that don't set their own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    volume: Option<u8>,
+
+    /// File the section was defined in, if it came from an included file
+    #[serde(skip)]
+    source: Option<PathBuf>,
+}
+
+impl Section {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn start(&self) -> &str {
+        &self.start
+    }
+
+    pub fn volume(&self) -> Option<u8> {
+        self.volume
+    }
+
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    pub fn set_source(&mut self, source: Option<PathBuf>) {
+        self.source = source;
+    }
+}
+
+#[allow(dead_code)]
+impl Section {
+    pub fn new(name: impl ToString, start: impl ToString) -> Self {
+        Self {
+            name: name.to_string(),
+            start: start.to_string(),
+            volume: None,
+            source: None,
+        }
+    }
+
+    pub fn with_volume(mut self, volume: u8) -> Self {
+        self.volume = Some(volume);
+        self
+    }
+}
+
+impl Script {
+    /// Sections paired with the index of their first cue, in cue list order.
+    /// Sections that don't start at a known cue are left out.
+    pub fn section_starts(&self) -> Vec<(usize, &Section)> {
+        let index: HashMap<_, _> = self
+            .cuelist()
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.label(), i))
+            .collect();
+
+        let mut starts: Vec<_> = self
+            .sections()
+            .iter()
+            .filter_map(|s| Some((*index.get(s.start())?, s)))
+            .collect();
+
+        starts.sort_by_key(|(i, _)| *i);
+        starts
+    }
+
+    /// The section the cue at `idx` falls in
+    pub fn section_of(&self, idx: usize) -> Option<&Section> {
+        let starts = self.section_starts();
+        let n = starts.partition_point(|(start, _)| *start <= idx);
+        n.checked_sub(1).map(|n| starts[n].1)
+    }
+
+    /// The section each cue falls in, in cue list order
+    pub fn cue_sections(&self) -> Vec<Option<&Section>> {
+        let mut starts = self.section_starts().into_iter().peekable();
+        let mut section = None;
+
+        (0..self.cuelist().len())
+            .map(|i| {
+                while let Some((_, s)) = starts.next_if(|(start, _)| *start <= i) {
+                    section = Some(s);
+                }
+                section
+            })
+            .collect()
+    }
+
+    /// Every cue with its section's defaults filled in
+    pub fn resolved_cuelist(&self) -> Vec<Cue> {
+        self.cuelist()
+            .iter()
+            .zip(self.cue_sections())
+            .map(|(cue, section)| self.resolve_in(cue, section))
+            .collect()
+    }
+
+    /// The cue at `idx` with its section's defaults filled in
+    pub fn resolve(&self, idx: usize) -> Option<Cue> {
+        Some(self.resolve_in(self.cuelist().get(idx)?, self.section_of(idx)))
+    }
+
+    /// Fill in the volume of `cue` from its section, or else the script's defaults
+    fn resolve_in(&self, cue: &Cue, section: Option<&Section>) -> Cue {
+        let volume = section
+            .and_then(Section::volume)
+            .or(self.defaults().volume());
+
+        match volume {
+            Some(volume) => {
+                let action = cue.action().clone().with_default_volume(volume);
+                cue.clone().with_action(action)
+            }
+            None => cue.clone(),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use time::Time;
+
+use super::actions::FadeCurve;
+use crate::util::defaults;
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct GoSettings {
+    #[serde_as(as = "serde_with::DurationSecondsWithFrac")]
+    #[schemars(with = "f64")]
+    #[serde(default = "defaults::default_go_lockout")]
+    lockout: Duration,
+
+    #[serde(default)]
+    standby: bool,
+}
+
+impl Default for GoSettings {
+    fn default() -> Self {
+        Self {
+            lockout: defaults::default_go_lockout(),
+            standby: false,
+        }
+    }
+}
+
+impl GoSettings {
+    pub fn lockout(&self) -> Duration {
+        self.lockout
+    }
+
+    pub fn standby(&self) -> bool {
+        self.standby
+    }
+}
+
+#[allow(dead_code)]
+impl GoSettings {
+    pub fn with_lockout(mut self, lockout: impl Into<Duration>) -> Self {
+        self.lockout = lockout.into();
+        self
+    }
+
+    pub fn with_standby(mut self) -> Self {
+        self.standby = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct Schedule {
+    #[serde(default, with = "crate::util::serde::time_of_day")]
+    #[schemars(with = "Option<String>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    house_open: Option<Time>,
+
+    #[serde(default, with = "crate::util::serde::time_of_day")]
+    #[schemars(with = "Option<String>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    curtain: Option<Time>,
+}
+
+impl Schedule {
+    pub fn house_open(&self) -> Option<Time> {
+        self.house_open
+    }
+
+    pub fn curtain(&self) -> Option<Time> {
+        self.curtain
+    }
+}
+
+#[allow(dead_code)]
+impl Schedule {
+    pub fn with_house_open(mut self, time: Time) -> Self {
+        self.house_open = Some(time);
+        self
+    }
+
+    pub fn with_curtain(mut self, time: Time) -> Self {
+        self.curtain = Some(time);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct CacheSettings {
+    #[serde(default = "defaults::default_cache_budget")]
+    budget: u32,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            budget: defaults::default_cache_budget(),
+        }
+    }
+}
+
+impl CacheSettings {
+    /// Maximum size of decoded audio kept in memory, in megabytes
+    pub fn budget(&self) -> u32 {
+        self.budget
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct LogSettings {
+    #[serde(default = "defaults::default_log_dir")]
+    dir: PathBuf,
+
+    #[serde(default = "defaults::default_log_keep")]
+    keep: usize,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            dir: defaults::default_log_dir(),
+            keep: defaults::default_log_keep(),
+        }
+    }
+}
+
+impl LogSettings {
+    /// Folder show logs are written to for the script at `script`, which
+    /// the configured folder is relative to
+    pub fn dir_for(&self, script: &Path) -> PathBuf {
+        script.parent().unwrap_or(Path::new("")).join(&self.dir)
+    }
+
+    /// Number of show logs to keep, including the current one
+    pub fn keep(&self) -> usize {
+        self.keep
+    }
+}
+
+/// Values filled in for every cue that doesn't set its own
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct Defaults {
+    /// Volume of playback and playlist cues
+    #[serde(skip_serializing_if = "Option::is_none")]
+    volume: Option<u8>,
+
+    /// Duration of fade cues
+    #[serde_as(as = "Option<serde_with::DurationSecondsWithFrac>")]
+    #[schemars(with = "Option<f64>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fade_duration: Option<Duration>,
+
+    /// Curve of fade cues
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fade_curve: Option<FadeCurve>,
+}
+
+#[allow(dead_code)]
+impl Defaults {
+    pub fn volume(&self) -> Option<u8> {
+        self.volume
+    }
+
+    pub fn fade_duration(&self) -> Option<Duration> {
+        self.fade_duration
+    }
+
+    pub fn fade_curve(&self) -> Option<FadeCurve> {
+        self.fade_curve
+    }
+
+    pub fn with_volume(mut self, volume: u8) -> Self {
+        self.volume = Some(volume);
+        self
+    }
+
+    pub fn with_fade_duration(mut self, duration: Duration) -> Self {
+        self.fade_duration = Some(duration);
+        self
+    }
+
+    pub fn with_fade_curve(mut self, curve: FadeCurve) -> Self {
+        self.fade_curve = Some(curve);
+        self
+    }
+}
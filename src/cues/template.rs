@@ -0,0 +1,208 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::prelude::*;
+
+use super::{Cue, Defaults};
+
+/// Action keys, with the field their short string form stands for
+const ACTIONS: [(&str, &str); 4] = [
+    ("playback", "file"),
+    ("playlist", "folder"),
+    ("fade", "target"),
+    ("stop", "target"),
+];
+
+/// Where each of the `defaults` goes: default name, action, and field in that action.
+/// The default volume isn't filled in here, since a section's volume overrides
+/// it; it's applied when cues are resolved instead.
+const DEFAULT_FIELDS: [(&str, &str, &str); 2] = [
+    ("fade_duration", "fade", "duration"),
+    ("fade_curve", "fade", "curve"),
+];
+
+/// Fills in templates and defaults on the raw YAML of each cue, before it's parsed
+pub struct Expander {
+    defaults: Mapping,
+    templates: Mapping,
+}
+
+impl Expander {
+    pub fn new(defaults: &Defaults, templates: &Mapping) -> Result<Self> {
+        let defaults = match serde_yaml::to_value(defaults)? {
+            Value::Mapping(m) => m,
+            _ => Mapping::new(),
+        };
+
+        Ok(Self {
+            defaults,
+            templates: templates.clone(),
+        })
+    }
+
+    /// Read the `defaults` and `templates` of a script that hasn't been parsed yet
+    pub fn from_root(root: &Value) -> Result<Self> {
+        let defaults = match root.get("defaults") {
+            Some(d) => serde_yaml::from_value(d.clone())?,
+            None => Defaults::default(),
+        };
+
+        let templates = match root.get("templates") {
+            Some(Value::Mapping(t)) => t.clone(),
+            _ => Mapping::new(),
+        };
+
+        Self::new(&defaults, &templates)
+    }
+
+    /// Expand every cue in the `cuelist` of a script file
+    pub fn expand_file(&self, file: &mut Value) -> Result<()> {
+        let Some(Value::Sequence(cues)) = file.get_mut("cuelist") else {
+            return Ok(());
+        };
+
+        cues.iter_mut().try_for_each(|cue| self.expand_cue(cue))
+    }
+
+    /// Merge in the template the cue `extends`, then fill in the defaults.
+    /// Values set on the cue itself always win.
+    pub fn expand_cue(&self, cue: &mut Value) -> Result<()> {
+        // Anything that isn't a mapping is left for the parser to complain about
+        let Value::Mapping(map) = cue else {
+            return Ok(());
+        };
+
+        normalize(map);
+
+        if let Some(name) = map.get("extends").and_then(Value::as_str) {
+            let label = map.get("label").and_then(Value::as_str).unwrap_or_default();
+            let template = self.template(label, name, &mut Vec::new())?;
+            merge(map, &template);
+        }
+
+        for (default, action, field) in DEFAULT_FIELDS {
+            let (Some(value), Some(Value::Mapping(action))) =
+                (self.defaults.get(default), map.get_mut(action))
+            else {
+                continue;
+            };
+
+            if !action.contains_key(field) {
+                action.insert(field.into(), value.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The template `name` merged with the templates it extends
+    fn template(&self, label: &str, name: &str, seen: &mut Vec<String>) -> Result<Mapping> {
+        if seen.iter().any(|s| s == name) {
+            return Err(FatalError::TemplateCycle(name.to_string()));
+        }
+
+        let Some(Value::Mapping(template)) = self.templates.get(name) else {
+            return Err(FatalError::UnknownTemplate(
+                label.to_string(),
+                name.to_string(),
+            ));
+        };
+
+        let mut template = template.clone();
+        normalize(&mut template);
+
+        if let Some(Value::String(parent)) = template.remove("extends") {
+            seen.push(name.to_string());
+            let parent = self.template(label, &parent, seen)?;
+            merge(&mut template, &parent);
+        }
+
+        Ok(template)
+    }
+
+    /// Serialize `cue`, leaving out anything its template or the defaults
+    /// would fill back in when it's loaded again
+    pub fn compact_cue(&self, cue: &Cue) -> Result<Value> {
+        let value = serde_yaml::to_value(cue)?;
+        let Value::Mapping(map) = &value else {
+            return Ok(value);
+        };
+
+        // What the template and defaults alone would give this cue
+        let mut base = Mapping::new();
+        for key in ["label", "extends"] {
+            if let Some(v) = map.get(key) {
+                base.insert(key.into(), v.clone());
+            }
+        }
+        for (action, _) in ACTIONS.iter().filter(|(a, _)| map.contains_key(*a)) {
+            base.insert((*action).into(), Value::Mapping(Mapping::new()));
+        }
+
+        let mut base = Value::Mapping(base);
+        self.expand_cue(&mut base)?;
+
+        let mut compact = map.clone();
+        normalize(&mut compact);
+
+        match base {
+            Value::Mapping(base) if strip(&mut compact, &base) => Ok(Value::Mapping(compact)),
+            _ => Ok(value),
+        }
+    }
+}
+
+/// Turn short forms like `stop: all` into mappings like `stop: {target: all}`
+fn normalize(cue: &mut Mapping) {
+    for (action, field) in ACTIONS {
+        if let Some(value) = cue.get_mut(action) {
+            if value.is_string() {
+                let mut expanded = Mapping::new();
+                expanded.insert(field.into(), std::mem::take(value));
+                *value = Value::Mapping(expanded);
+            }
+        }
+    }
+}
+
+/// Copy everything from `base` that isn't already in `into`, recursing into mappings
+fn merge(into: &mut Mapping, base: &Mapping) {
+    for (key, value) in base {
+        match (into.get_mut(key), value) {
+            (Some(Value::Mapping(into)), Value::Mapping(base)) => merge(into, base),
+            (Some(_), _) => {}
+            (None, value) => {
+                into.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Remove everything from `map` that's the same in `base`. Returns whether anything was removed.
+fn strip(map: &mut Mapping, base: &Mapping) -> bool {
+    let mut removed = false;
+
+    for (key, value) in base {
+        if key.as_str().is_some_and(|k| k == "label" || k == "extends") {
+            continue;
+        }
+
+        match (map.get_mut(key), value) {
+            (Some(Value::Mapping(map)), Value::Mapping(base)) => removed |= strip(map, base),
+            (Some(v), base) if same(v, base) => {
+                map.remove(key);
+                removed = true;
+            }
+            _ => {}
+        }
+    }
+
+    removed
+}
+
+/// Whether two values are equal, counting numbers like `3` and `3.0` as the same
+fn same(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
@@ -1,6 +1,8 @@
 use std::time::Duration;
 
 use super::*;
+use crate::prelude::FatalError;
+use crate::util::fs::TempDir;
 
 #[test]
 fn deserialize_full() {
@@ -113,3 +115,362 @@ cuelist:
 
     assert_eq!(de, show);
 }
+
+#[test]
+fn deserialize_go_settings() {
+    let yaml = "
+go:
+  lockout: 1.5
+  standby: true
+cuelist:
+- label: SQ1
+  playback: sound.wav
+";
+
+    let show = Script::new(vec![Cue::new(
+        "SQ1",
+        cue::CueAction::Playback(actions::PlaybackCue::new("sound.wav")),
+    )])
+    .with_go(
+        GoSettings::default()
+            .with_lockout(Duration::from_millis(1500))
+            .with_standby(),
+    );
+
+    let de = serde_yaml::from_str::<Script>(yaml).expect("Failed to deserialize");
+
+    assert_eq!(de, show);
+}
+
+#[test]
+fn action_summary() {
+    let playback = cue::CueAction::Playback(
+        actions::PlaybackCue::new("sound.wav")
+            .fade_in_for(Duration::from_secs(5))
+            .fade_out_for(Duration::from_millis(2500)),
+    );
+    let fade = cue::CueAction::Fade(
+        actions::FadeCue::new("SQ1")
+            .to_volume(40)
+            .for_duration(Duration::from_secs(10)),
+    );
+
+    assert_eq!(
+        playback.to_string(),
+        "Play sound.wav, fade in 5s, fade out 2.5s"
+    );
+    assert_eq!(fade.to_string(), "Fade SQ1 to 40% over 10s");
+}
+
+#[test]
+fn deserialize_schedule() {
+    let yaml = "
+schedule:
+  house_open: '19:30'
+  curtain: 20:00:30
+cuelist: []
+";
+
+    let show = Script::new(vec![]).with_schedule(
+        Schedule::default()
+            .with_house_open(time::macros::time!(19:30))
+            .with_curtain(time::macros::time!(20:00:30)),
+    );
+
+    let de = serde_yaml::from_str::<Script>(yaml).expect("Failed to deserialize");
+
+    assert_eq!(de, show);
+
+    assert!(serde_yaml::from_str::<Script>("schedule:\n  curtain: 25:00\ncuelist: []").is_err());
+}
+
+#[test]
+fn deserialize_preload() {
+    let yaml = "
+cache:
+  budget: 64
+cuelist:
+- label: SQ1
+  playback:
+    file: doorbell.wav
+    preload: memory
+";
+
+    let de = serde_yaml::from_str::<Script>(yaml).expect("Failed to deserialize");
+
+    assert_eq!(de.cache().budget(), 64);
+    assert_eq!(
+        de.cuelist()[0].action().to_string(),
+        "Play doorbell.wav, in memory"
+    );
+}
+
+#[test]
+fn serialize_round_trip() {
+    let show = Script::new(vec![
+        Cue::new("SQ1", actions::PlaybackCue::new("sound.wav")).with_hint("pg. 1"),
+        Cue::new(
+            "SQ2",
+            actions::PlaybackCue::new("music.mp3").fade_in_for(Duration::from_millis(2500)),
+        ),
+        Cue::new("SQ3", actions::FadeCue::new("SQ2").to_volume(40)),
+        Cue::new("SQ4", actions::StopCue::new("all")),
+    ]);
+
+    let yaml = serde_yaml::to_string(&show).expect("Failed to serialize");
+
+    assert!(yaml.contains("playback: sound.wav"));
+    assert!(!yaml.contains("description"));
+    assert_eq!(serde_yaml::from_str::<Script>(&yaml).unwrap(), show);
+}
+
+#[test]
+fn load_includes() {
+    let dir = TempDir::new("include");
+    std::fs::create_dir_all(dir.join("acts")).unwrap();
+
+    let write = |name: &str, yaml: &str| std::fs::write(dir.join(name), yaml).unwrap();
+    write(
+        "script.yaml",
+        "include: [acts/one.yaml]\ncuelist:\n- label: PRE\n  stop: all\n",
+    );
+    write(
+        "acts/one.yaml",
+        "include: [two.yaml]\ncuelist:\n- label: SQ1\n  stop: all\n",
+    );
+    write("acts/two.yaml", "cuelist:\n- label: SQ2\n  fade: SQ9\n");
+
+    let script = Script::load_from(dir.join("script.yaml")).unwrap();
+    assert_eq!(script.cue_names(), vec!["PRE", "SQ1", "SQ2"]);
+    assert_eq!(
+        script.cuelist()[1].source(),
+        Some(dir.join("acts/one.yaml").as_path())
+    );
+
+    let err = script.validate().unwrap_err().to_string();
+    assert!(err.starts_with(&dir.join("acts/two.yaml").display().to_string()));
+    assert!(err.ends_with("Invalid target in SQ2: SQ9"));
+
+    write("acts/two.yaml", "include: [one.yaml]\n");
+    assert!(matches!(
+        Script::load_from(dir.join("script.yaml")),
+        Err(FatalError::IncludeCycle(_))
+    ));
+}
+
+#[test]
+fn save_includes() {
+    let dir = TempDir::new("save");
+    let write = |name: &str, yaml: &str| std::fs::write(dir.join(name), yaml).unwrap();
+    write(
+        "script.yaml",
+        "include: [act.yaml]\ncuelist:\n- label: PRE\n  stop: all\n- label: SQ1\n  stop: all\n",
+    );
+    write(
+        "act.yaml",
+        "cuelist:\n- label: SQ2\n  stop: all\n- label: SQ3\n  stop: all\n",
+    );
+
+    let script = Script::load_from(dir.join("script.yaml")).unwrap();
+    let mut cues = script.cuelist().to_vec();
+
+    // Moving SQ2 into the script would put it back at the end when saved
+    cues.swap(1, 2);
+    let moved = script.clone().with_cuelist(cues.clone()).validate();
+    assert!(moved
+        .unwrap_err()
+        .to_string()
+        .ends_with("Cue SQ1 is after cues from a later include; cues can't move between files"));
+
+    cues.swap(1, 2);
+    cues.swap(2, 3);
+    script
+        .with_cuelist(cues)
+        .validate()
+        .unwrap()
+        .save_to(dir.join("script.yaml"))
+        .unwrap();
+    let saved = Script::load_from(dir.join("script.yaml")).unwrap();
+    assert_eq!(saved.cue_names(), vec!["PRE", "SQ1", "SQ3", "SQ2"]);
+    assert_eq!(
+        saved.cuelist()[2].source(),
+        Some(dir.join("act.yaml").as_path())
+    );
+}
+
+#[test]
+fn sort_within_sections() {
+    let cue = |label: &str, number: u32| {
+        Cue::new(label, actions::StopCue::new("all")).with_number(CueNumber::whole(number))
+    };
+    let script = Script::new(vec![
+        cue("SQ2", 2),
+        cue("SQ1", 1),
+        cue("SQ5", 5),
+        cue("SQ4", 4),
+        cue("SQ3", 3),
+    ])
+    .with_sections(vec![Section::new("Act 2", "SQ5")]);
+
+    let sorted = script.sort_by_number();
+    assert_eq!(sorted.cue_names(), vec!["SQ1", "SQ2", "SQ5", "SQ3", "SQ4"]);
+    assert_eq!(sorted.section_of(2).map(Section::name), Some("Act 2"));
+}
+
+#[test]
+fn section_defaults() {
+    let yaml = "
+sections:
+- name: Act 2
+  start: SQ3
+- name: Act 1
+  start: SQ1
+  volume: 60
+cuelist:
+- label: SQ1
+  playback: a.wav
+- label: SQ2
+  playback:
+    file: b.wav
+    volume: 90
+- label: SQ3
+  playback: c.wav
+";
+
+    let script = serde_yaml::from_str::<Script>(yaml).unwrap();
+
+    let names: Vec<_> = script
+        .section_starts()
+        .iter()
+        .map(|(i, s)| (*i, s.name()))
+        .collect();
+    assert_eq!(names, vec![(0, "Act 1"), (2, "Act 2")]);
+    assert_eq!(script.section_of(1).map(Section::name), Some("Act 1"));
+
+    let volumes: Vec<_> = script
+        .resolved_cuelist()
+        .iter()
+        .map(|c| match c.action() {
+            CueAction::Playback(p) => p.volume(),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(volumes, vec![Some(60), Some(90), None]);
+
+    let sections: Vec<_> = script
+        .cue_sections()
+        .iter()
+        .map(|s| s.map(Section::name))
+        .collect();
+    assert_eq!(sections, vec![Some("Act 1"), Some("Act 1"), Some("Act 2")]);
+
+    let mut renamed = script.sections().to_vec();
+    renamed[0] = Section::new("Act 1", "SQ3");
+    assert!(matches!(
+        script.with_sections(renamed).validate_without_files(),
+        Err(FatalError::DuplicateSection(name)) if name == "Act 1"
+    ));
+}
+
+#[test]
+fn default_volume_precedence() {
+    let yaml = "
+defaults:
+  volume: 50
+sections:
+- name: Act 1
+  start: SQ1
+  volume: 60
+- name: Act 2
+  start: SQ3
+cuelist:
+- label: SQ1
+  playback: a.wav
+- label: SQ2
+  playback:
+    file: b.wav
+    volume: 90
+- label: SQ3
+  playback: c.wav
+";
+
+    let mut value: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap();
+    Expander::from_root(&value)
+        .unwrap()
+        .expand_file(&mut value)
+        .unwrap();
+    let script: Script = serde_yaml::from_value(value).unwrap();
+
+    // The cue's own volume, then its section's, then the script's default
+    let volumes: Vec<_> = script
+        .resolved_cuelist()
+        .iter()
+        .map(|c| match c.action() {
+            CueAction::Playback(p) => p.volume(),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(volumes, vec![Some(60), Some(90), Some(50)]);
+}
+
+#[test]
+fn templates_and_defaults() {
+    let yaml = "
+defaults:
+  volume: 70
+  fade_duration: 2
+  fade_curve: s_curve
+templates:
+  music:
+    playback:
+      fade_in: 3
+  quiet_music:
+    extends: music
+    playback:
+      volume: 30
+cuelist:
+- label: SQ1
+  extends: quiet_music
+  playback: a.wav
+- label: SQ2
+  playback: b.wav
+- label: SQ3
+  fade: SQ1
+";
+
+    let mut value: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap();
+    let expander = Expander::from_root(&value).unwrap();
+    expander.expand_file(&mut value).unwrap();
+    let script: Script = serde_yaml::from_value(value).unwrap();
+
+    let [sq1, sq2, sq3] = script.cuelist() else {
+        panic!("Expected 3 cues");
+    };
+
+    let CueAction::Playback(p) = sq1.action() else {
+        panic!("Expected a playback cue");
+    };
+    assert_eq!(p.volume(), Some(30));
+    assert_eq!(p.fade_in(), Some(Duration::from_secs(3)));
+
+    // The default volume is only filled in once sections have had their say
+    assert_eq!(sq2.action(), &actions::PlaybackCue::new("b.wav").into());
+    assert_eq!(
+        script.resolve(1).unwrap().action(),
+        &actions::PlaybackCue::new("b.wav").with_volume(70).into()
+    );
+    assert_eq!(
+        sq3.action(),
+        &actions::FadeCue::new("SQ1")
+            .for_duration(Duration::from_secs(2))
+            .with_curve(actions::FadeCurve::SCurve)
+            .into()
+    );
+
+    let compact = |cue| serde_yaml::to_string(&expander.compact_cue(cue).unwrap()).unwrap();
+    assert_eq!(
+        compact(sq1),
+        "label: SQ1\nextends: quiet_music\nplayback:\n  file: a.wav\n"
+    );
+    assert_eq!(compact(sq3), "label: SQ3\nfade:\n  target: SQ1\n");
+}
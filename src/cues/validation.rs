@@ -1,42 +1,111 @@
+use std::collections::HashSet;
+
 use super::{cue::CueAction, Script};
 use crate::prelude::*;
 
 impl Script {
     pub fn validate(self) -> Result<Self> {
-        let mut valid_targets = self.cue_names();
-        valid_targets.push("all");
+        self.check(true)?;
+        Ok(self)
+    }
+
+    /// Validate everything but whether the files the cues play exist, for
+    /// checking edits as they're made
+    pub fn validate_without_files(self) -> Result<Self> {
+        self.check(false)?;
+        Ok(self)
+    }
+
+    fn check(&self, files: bool) -> Result<()> {
+        let mut valid_targets: HashSet<_> = self.cue_names().into_iter().collect();
+        valid_targets.insert("all");
+
+        let mut labels = HashSet::new();
+        let mut last_number = None;
+
+        // Cues are saved back to the file they came from, so each file's cues
+        // have to stay together and in the order the files are included
+        let file_index = |source: Option<&std::path::Path>| {
+            source
+                .and_then(|s| self.files().iter().position(|f| f == s))
+                .map_or(0, |i| i + 1)
+        };
+        let mut last_file = 0;
 
         for cue in self.cuelist() {
+            let in_file = |e: FatalError| match cue.source() {
+                Some(path) => FatalError::InFile(path.to_path_buf(), Box::new(e)),
+                None => e,
+            };
+
+            if !labels.insert(cue.label()) {
+                return Err(in_file(FatalError::DuplicateLabel(cue.label().to_string())));
+            }
+
+            let file = file_index(cue.source());
+            if file < last_file {
+                return Err(in_file(FatalError::CueFileOrder(cue.label().to_string())));
+            }
+            last_file = file;
+
+            if let Some(number) = cue.number() {
+                if let Some(last) = last_number.filter(|last| number <= *last) {
+                    return Err(in_file(FatalError::CueNumberOrder(
+                        cue.label().to_string(),
+                        number.to_string(),
+                        last.to_string(),
+                    )));
+                }
+                last_number = Some(number);
+            }
+
             match cue.action() {
                 CueAction::Playback(c) => {
-                    if !c.file().exists() {
-                        return Err(FatalError::CueFile(
+                    if files && !c.file().exists() {
+                        return Err(in_file(FatalError::CueFile(
                             cue.label().to_string(),
                             c.file().clone(),
-                        ));
+                        )));
                     }
                 }
                 CueAction::Fade(c) => {
-                    if !valid_targets.contains(&c.target()) {
-                        return Err(FatalError::CueTarget(
+                    if !valid_targets.contains(c.target()) {
+                        return Err(in_file(FatalError::CueTarget(
                             cue.label().to_string(),
                             c.target().to_string(),
-                        ));
+                        )));
                     }
                 }
                 CueAction::Stop(c) => {
-                    if !valid_targets.contains(&c.target()) {
-                        return Err(FatalError::CueTarget(
+                    if !valid_targets.contains(c.target()) {
+                        return Err(in_file(FatalError::CueTarget(
                             cue.label().to_string(),
                             c.target().to_string(),
-                        ));
+                        )));
                     }
                 }
                 _ => {}
             }
         }
 
-        Ok(self)
+        // Sections are collapsed by name, so names have to be unique
+        let mut sections = HashSet::new();
+        for section in self.sections() {
+            let e = if !sections.insert(section.name()) {
+                FatalError::DuplicateSection(section.name().to_string())
+            } else if !labels.contains(section.start()) {
+                FatalError::SectionStart(section.name().to_string(), section.start().to_string())
+            } else {
+                continue;
+            };
+
+            return Err(match section.source() {
+                Some(path) => FatalError::InFile(path.to_path_buf(), Box::new(e)),
+                None => e,
+            });
+        }
+
+        Ok(())
     }
 }
 
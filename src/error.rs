@@ -15,9 +15,66 @@ pub enum FatalError {
     #[error(transparent)]
     Parse(#[from] serde_yaml::Error),
 
+    #[error(transparent)]
+    ParseJson(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    ParseToml(#[from] toml::de::Error),
+
+    #[error(transparent)]
+    WriteToml(#[from] toml::ser::Error),
+
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    #[error("{} already exists; use --force to replace it", .0.display())]
+    Exists(PathBuf),
+
+    #[error("Unknown script format for {}; use .yaml, .json or .toml", .0.display())]
+    UnknownFormat(PathBuf),
+
+    #[error("Unknown cue sheet format for {}; use .md, .html or .csv", .0.display())]
+    UnknownSheetFormat(PathBuf),
+
+    #[error("Unknown report format for {}; use .md or .json", .0.display())]
+    UnknownReportFormat(PathBuf),
+
+    #[error("No show logs in {}", .0.display())]
+    NoShowLogs(PathBuf),
+
+    #[error("{} is empty", .0.display())]
+    EmptyLog(PathBuf),
+
     #[error("Invalid target in {0}: {1}")]
     CueTarget(String, String),
 
+    #[error("{}: {1}", .0.display())]
+    InFile(PathBuf, Box<FatalError>),
+
+    #[error("{} includes itself", .0.display())]
+    IncludeCycle(PathBuf),
+
+    #[error("Duplicate section name: {0}")]
+    DuplicateSection(String),
+
+    #[error("Section {0} starts at unknown cue {1}")]
+    SectionStart(String, String),
+
+    #[error("Cue {0} is numbered {1}, which doesn't come after {2}")]
+    CueNumberOrder(String, String, String),
+
+    #[error("Cue {0} extends unknown template {1}")]
+    UnknownTemplate(String, String),
+
+    #[error("Template {0} extends itself")]
+    TemplateCycle(String),
+
+    #[error("Cue {0} is after cues from a later include; cues can't move between files")]
+    CueFileOrder(String),
+
+    #[error("Duplicate cue label: {0}")]
+    DuplicateLabel(String),
+
     #[error("Invalid audio file in {0}: {1}")]
     CueFile(String, PathBuf),
 
@@ -38,4 +95,7 @@ pub enum FatalError {
 
     #[error(transparent)]
     OutputSetup(#[from] rodio::StreamError),
+
+    #[error(transparent)]
+    OutputPlay(#[from] rodio::PlayError),
 }
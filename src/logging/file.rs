@@ -0,0 +1,251 @@
+use std::{
+    fmt::Display,
+    fs,
+    io::{self, LineWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Mutex,
+};
+
+use log::{Level, Log};
+use time::{
+    format_description::well_known::Rfc3339, macros::format_description, OffsetDateTime, UtcOffset,
+};
+
+const PREFIX: &str = "show-";
+const EXTENSION: &str = "log";
+
+/// Writes every record to its own file for each performance, so the show
+/// can be gone over afterwards
+pub struct FileLogger {
+    offset: UtcOffset,
+    path: PathBuf,
+    file: Mutex<LineWriter<fs::File>>,
+}
+
+impl FileLogger {
+    /// Start a new log in `dir`, named after the current time, deleting the
+    /// oldest logs so that only `keep` are left. Must be called before other
+    /// threads are spawned to get the local time zone.
+    pub fn create(dir: &Path, keep: usize) -> io::Result<Self> {
+        let offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+        let now = OffsetDateTime::now_utc().to_offset(offset);
+
+        let name = now
+            .format(format_description!(
+                "[year]-[month]-[day]_[hour]-[minute]-[second]"
+            ))
+            .map_err(io::Error::other)?;
+
+        fs::create_dir_all(dir)?;
+
+        // Runs started within the same second get a counter. `_` comes
+        // after `.`, so these still sort after the first run's log.
+        let mut path = dir.join(format!("{PREFIX}{name}.{EXTENSION}"));
+        for n in 2.. {
+            if !path.exists() {
+                break;
+            }
+            path = dir.join(format!("{PREFIX}{name}_{n:03}.{EXTENSION}"));
+        }
+
+        let file = fs::OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(&path)?;
+        rotate(dir, keep)?;
+
+        Ok(Self {
+            offset,
+            path,
+            file: Mutex::new(LineWriter::new(file)),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Show logs in `dir`, oldest first
+pub fn show_logs(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut logs: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            name.starts_with(PREFIX) && path.extension().is_some_and(|e| e == EXTENSION)
+        })
+        .collect();
+
+    // The names start with the time, so they sort oldest first
+    logs.sort();
+    Ok(logs)
+}
+
+fn rotate(dir: &Path, keep: usize) -> io::Result<()> {
+    let logs = show_logs(dir)?;
+
+    for old in &logs[..logs.len().saturating_sub(keep.max(1))] {
+        fs::remove_file(old)?;
+    }
+
+    Ok(())
+}
+
+/// Writes every record it's given; `Logger` decides which ones
+impl Log for FileLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let line = LogLine {
+            time: OffsetDateTime::now_utc().to_offset(self.offset),
+            level: record.level(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+
+        // There's nowhere to report a failed write to without recursing
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "{line}");
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// One record in a show log, written as a single line like
+/// `2023-07-29T19:30:02.125-05:00 INFO  encue::app::state GO SQ1`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    pub time: OffsetDateTime,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Not a show log line: {0}")]
+pub struct ParseLogLineError(String);
+
+impl Display for LogLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let time = self.time.format(&Rfc3339).map_err(|_| std::fmt::Error)?;
+        // Keep each record on one line
+        let message = self.message.replace('\\', "\\\\").replace('\n', "\\n");
+
+        write!(f, "{time} {:<5} {} {message}", self.level, self.target)
+    }
+}
+
+impl FromStr for LogLine {
+    type Err = ParseLogLineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseLogLineError(s.to_string());
+
+        let (time, rest) = s.split_once(' ').ok_or_else(err)?;
+        let (level, rest) = rest.trim_start().split_once(' ').ok_or_else(err)?;
+        let (target, message) = rest
+            .trim_start()
+            .split_once(' ')
+            .unwrap_or((rest.trim_start(), ""));
+
+        let mut unescaped = String::with_capacity(message.len());
+        let mut chars = message.chars();
+        while let Some(c) = chars.next() {
+            match (c, chars.clone().next()) {
+                ('\\', Some('n')) => {
+                    unescaped.push('\n');
+                    chars.next();
+                }
+                ('\\', Some('\\')) => {
+                    unescaped.push('\\');
+                    chars.next();
+                }
+                (c, _) => unescaped.push(c),
+            }
+        }
+
+        Ok(Self {
+            time: OffsetDateTime::parse(time, &Rfc3339).map_err(|_| err())?,
+            level: level.parse().map_err(|_| err())?,
+            target: target.to_string(),
+            message: unescaped,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use log::{Level, Log};
+    use time::macros::datetime;
+
+    use super::{show_logs, FileLogger, LogLine};
+    use crate::util::fs::TempDir;
+
+    #[test]
+    fn rotates_old_logs() {
+        let dir = TempDir::new("logs");
+        for old in [
+            "show-2020-01-01_20-00-00.log",
+            "show-2020-01-02_20-00-00_002.log",
+            "show-2020-01-02_20-00-00.log",
+            "notes.txt",
+        ] {
+            fs::write(dir.join(old), "").unwrap();
+        }
+
+        let logger = FileLogger::create(dir.path(), 2).unwrap();
+        logger.log(
+            &log::Record::builder()
+                .level(Level::Info)
+                .target("encue::test")
+                .args(format_args!("GO SQ1"))
+                .build(),
+        );
+
+        // A second run in the same second sorts after the first
+        let logs = show_logs(dir.path()).unwrap();
+        assert_eq!(
+            logs,
+            [
+                dir.join("show-2020-01-02_20-00-00_002.log"),
+                logger.path().to_path_buf()
+            ]
+        );
+        assert!(dir.join("notes.txt").exists());
+
+        let text = fs::read_to_string(logger.path()).unwrap();
+        let line: LogLine = text.trim_end().parse().unwrap();
+        assert_eq!((line.level, line.message.as_str()), (Level::Info, "GO SQ1"));
+    }
+
+    #[test]
+    fn log_line_round_trip() {
+        let line = LogLine {
+            time: datetime!(2023-07-29 19:30:02.125 -5),
+            level: Level::Info,
+            target: "encue::app::state".to_string(),
+            message: "GO SQ1\nwith a \\n in it".to_string(),
+        };
+
+        let written = line.to_string();
+        assert_eq!(
+            written,
+            "2023-07-29T19:30:02.125-05:00 INFO  encue::app::state GO SQ1\\nwith a \\\\n in it"
+        );
+        assert_eq!(written.parse::<LogLine>().unwrap(), line);
+        assert!("not a log line".parse::<LogLine>().is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use std::{fmt::Display, str::FromStr};
+
+use log::LevelFilter;
+
+const LEVELS: [LevelFilter; 6] = [
+    LevelFilter::Off,
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+    LevelFilter::Trace,
+];
+
+/// Which messages are logged: a default level for all of encue, and levels
+/// for particular modules, written like `warn,encue::cues::actions::fade=trace`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLevels {
+    default: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("`{0}` is not a log level, expected one of off, error, warn, info, debug or trace")]
+pub struct ParseLogLevelsError(String);
+
+impl Default for LogLevels {
+    fn default() -> Self {
+        Self {
+            default: LevelFilter::Debug,
+            modules: Vec::new(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl LogLevels {
+    pub fn new(default: LevelFilter) -> Self {
+        Self {
+            default,
+            modules: Vec::new(),
+        }
+    }
+
+    pub fn with_module(mut self, module: impl ToString, level: LevelFilter) -> Self {
+        let module = module.to_string();
+        self.modules.retain(|(m, _)| *m != module);
+        self.modules.push((module, level));
+        self
+    }
+
+    pub fn default_level(&self) -> LevelFilter {
+        self.default
+    }
+
+    /// The level for messages from `target`, set by the most specific module
+    /// that contains it. Other crates are only logged if they're named.
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        let contains = |module: &str| {
+            target == module
+                || target
+                    .strip_prefix(module)
+                    .is_some_and(|rest| rest.starts_with("::"))
+        };
+
+        self.modules
+            .iter()
+            .filter(|(module, _)| contains(module))
+            .max_by_key(|(module, _)| module.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(if contains("encue") {
+                self.default
+            } else {
+                LevelFilter::Off
+            })
+    }
+
+    /// The most detailed level anything is logged at
+    pub fn max(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+
+    /// Log more detail by default
+    pub fn raise(&mut self) {
+        let i = LEVELS.iter().position(|l| *l == self.default).unwrap_or(0);
+        self.default = LEVELS[(i + 1).min(LEVELS.len() - 1)];
+    }
+
+    /// Log less detail by default
+    pub fn lower(&mut self) {
+        let i = LEVELS.iter().position(|l| *l == self.default).unwrap_or(0);
+        self.default = LEVELS[i.saturating_sub(1)];
+    }
+}
+
+impl FromStr for LogLevels {
+    type Err = ParseLogLevelsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let level = |l: &str| {
+            l.trim()
+                .parse::<LevelFilter>()
+                .map_err(|_| ParseLogLevelsError(l.trim().to_string()))
+        };
+
+        let mut levels = Self::default();
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, l)) => levels = levels.with_module(module.trim(), level(l)?),
+                None => match level(directive) {
+                    Ok(l) => levels.default = l,
+                    // A module on its own logs everything from it
+                    Err(_) if directive.contains("::") || directive == "encue" => {
+                        levels = levels.with_module(directive, LevelFilter::Trace)
+                    }
+                    Err(e) => return Err(e),
+                },
+            }
+        }
+
+        Ok(levels)
+    }
+}
+
+impl Display for LogLevels {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.default.as_str().to_lowercase())?;
+        for (module, level) in &self.modules {
+            write!(f, ",{module}={}", level.as_str().to_lowercase())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use log::LevelFilter;
+
+    use super::LogLevels;
+
+    #[test]
+    fn per_module_levels() {
+        let levels: LogLevels = "warn, encue::cues::actions::fade=trace,rodio=info"
+            .parse()
+            .unwrap();
+
+        assert_eq!(levels.level_for("encue::app::state"), LevelFilter::Warn);
+        assert_eq!(
+            levels.level_for("encue::cues::actions::fade"),
+            LevelFilter::Trace
+        );
+        assert_eq!(
+            levels.level_for("encue::cues::actions::fader"),
+            LevelFilter::Warn
+        );
+        assert_eq!(levels.level_for("rodio::sink"), LevelFilter::Info);
+        assert_eq!(levels.level_for("cpal"), LevelFilter::Off);
+        assert_eq!(levels.max(), LevelFilter::Trace);
+        assert_eq!(
+            levels.to_string(),
+            "warn,encue::cues::actions::fade=trace,rodio=info"
+        );
+        assert_eq!(levels.to_string().parse::<LogLevels>().unwrap(), levels);
+
+        assert!("loud".parse::<LogLevels>().is_err());
+        assert!("encue=loud".parse::<LogLevels>().is_err());
+
+        let mut levels = LogLevels::new(LevelFilter::Error);
+        levels.lower();
+        levels.lower();
+        assert_eq!(levels.default_level(), LevelFilter::Off);
+        levels.raise();
+        assert_eq!(levels.default_level(), LevelFilter::Error);
+    }
+}
@@ -0,0 +1,384 @@
+mod file;
+mod levels;
+mod report;
+
+use std::{
+    collections::VecDeque,
+    path::Path,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use log::{Level, LevelFilter, Log};
+use ratatui::widgets::{Block, Widget};
+use time::{macros::format_description, OffsetDateTime};
+
+use crate::prelude::*;
+use ratatui::prelude::*;
+
+use file::{show_logs, FileLogger, LogLine};
+pub use levels::LogLevels;
+pub use report::{latest_log, log_go, ReportFormat, ShowReport};
+
+#[derive(Clone)]
+pub struct TuiLogger {
+    state: Arc<Mutex<TuiLoggerState>>,
+}
+
+/// Number of records kept for the log pane; older ones are only in the show log
+const HISTORY: usize = 5000;
+
+#[derive(Clone)]
+pub struct LogEntry {
+    /// Counts up from the first record, so entries can be found again after older ones are dropped
+    pub id: u64,
+    pub level: Level,
+    pub time: OffsetDateTime,
+    pub message: String,
+    /// `message` in lower case, for searching
+    lowercase: String,
+}
+
+/// The most recent records, for the log pane
+#[derive(Default)]
+pub struct TuiLoggerState {
+    entries: VecDeque<LogEntry>,
+    next_id: u64,
+}
+
+impl TuiLoggerState {
+    fn push(&mut self, level: Level, time: OffsetDateTime, message: String) {
+        if self.entries.len() >= HISTORY {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(LogEntry {
+            id: self.next_id,
+            level,
+            time,
+            lowercase: message.to_lowercase(),
+            message,
+        });
+        self.next_id += 1;
+    }
+
+    /// Entries that pass `filter`, oldest first
+    pub fn filtered<'a>(
+        &'a self,
+        filter: &'a LogFilter,
+    ) -> impl DoubleEndedIterator<Item = &'a LogEntry> {
+        self.entries.iter().filter(|e| filter.matches(e))
+    }
+
+    /// Copies of the `height` entries that pass `filter` and end at the one
+    /// with id `anchor`, or the newest. Copied so the lock on the history
+    /// doesn't have to be held while they're drawn.
+    pub fn visible(&self, filter: &LogFilter, anchor: Option<u64>, height: usize) -> Vec<LogEntry> {
+        let mut shown: Vec<_> = self
+            .filtered(filter)
+            .rev()
+            .filter(|e| anchor.is_none_or(|anchor| e.id <= anchor))
+            .take(height)
+            .collect();
+        shown.reverse();
+
+        // Fill the pane even when scrolled to the top
+        if shown.len() < height {
+            shown = self.filtered(filter).take(height).collect();
+        }
+
+        shown.into_iter().cloned().collect()
+    }
+}
+
+/// Which records the log pane shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFilter {
+    pub level: LevelFilter,
+    /// Only show records containing this, ignoring case
+    search: String,
+    /// `search` in lower case
+    lowercase: String,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            level: LevelFilter::Trace,
+            search: String::new(),
+            lowercase: String::new(),
+        }
+    }
+}
+
+impl LogFilter {
+    pub fn search(&self) -> &str {
+        &self.search
+    }
+
+    pub fn set_search(&mut self, search: impl ToString) {
+        self.search = search.to_string();
+        self.lowercase = self.search.to_lowercase();
+    }
+
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        entry.level <= self.level && entry.lowercase.contains(&self.lowercase)
+    }
+}
+
+/// Sends every record to the log pane, and to the show log if there is one
+struct Logger {
+    levels: Arc<RwLock<LogLevels>>,
+    tui: TuiLogger,
+    file: Option<FileLogger>,
+}
+
+impl Logger {
+    fn shown(&self, metadata: &log::Metadata) -> bool {
+        self.levels
+            .read()
+            .is_ok_and(|l| metadata.level() <= l.level_for(metadata.target()))
+    }
+
+    /// The show log always keeps what it needs for a show report, however quiet the log pane is
+    fn kept(&self, metadata: &log::Metadata) -> bool {
+        self.shown(metadata)
+            || (metadata.target().starts_with("encue") && metadata.level() <= Level::Info)
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.kept(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.shown(record.metadata()) {
+            self.tui.log(record);
+        }
+        if let Some(file) = self.file.as_ref().filter(|_| self.kept(record.metadata())) {
+            file.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file {
+            file.flush();
+        }
+    }
+}
+
+/// What the app needs to show and control logging once it's started
+pub struct LogHandle {
+    history: Arc<Mutex<TuiLoggerState>>,
+    levels: Arc<RwLock<LogLevels>>,
+}
+
+impl LogHandle {
+    pub fn history(&self) -> &Arc<Mutex<TuiLoggerState>> {
+        &self.history
+    }
+
+    pub fn levels(&self) -> LogLevels {
+        self.levels.read().map(|l| l.clone()).unwrap_or_default()
+    }
+
+    pub fn set_levels(&self, levels: LogLevels) {
+        set_max_level(&levels);
+        if let Ok(mut current) = self.levels.write() {
+            *current = levels;
+        }
+    }
+}
+
+fn set_max_level(levels: &LogLevels) {
+    log::set_max_level(levels.max().max(LevelFilter::Info));
+}
+
+/// Start logging to the log pane, and to a new show log in `dir` keeping the
+/// newest `keep` logs. The app carries on without a show log if it can't be created.
+pub fn init(levels: LogLevels, dir: &Path, keep: usize) -> Result<LogHandle> {
+    set_max_level(&levels);
+
+    let tui = TuiLogger::new();
+    let levels = Arc::new(RwLock::new(levels));
+    let handle = LogHandle {
+        history: tui.state.clone(),
+        levels: levels.clone(),
+    };
+
+    let (file, created) = match FileLogger::create(dir, keep) {
+        Ok(file) => {
+            let path = file.path().to_path_buf();
+            (Some(file), Ok(path))
+        }
+        Err(e) => (None, Err(e)),
+    };
+
+    log::set_boxed_logger(Box::new(Logger { levels, tui, file }))?;
+
+    match created {
+        Ok(path) => log::info!("Writing show log to {}", path.display()),
+        Err(e) => log::error!("Couldn't create a show log in {}: {e}", dir.display()),
+    }
+
+    Ok(handle)
+}
+
+impl TuiLogger {
+    pub fn new() -> Self {
+        Self {
+            state: Default::default(),
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct LogWidget<'a> {
+    block: Option<Block<'a>>,
+    entries: Vec<LogEntry>,
+}
+
+impl<'a> LogWidget<'a> {
+    pub fn block(mut self, block: Block<'a>) -> LogWidget<'a> {
+        self.block = Some(block);
+        self
+    }
+
+    /// The entries to show, oldest first, from [`TuiLoggerState::visible`]
+    pub fn entries(mut self, entries: Vec<LogEntry>) -> LogWidget<'a> {
+        self.entries = entries;
+        self
+    }
+}
+
+impl Widget for LogWidget<'_> {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        let text_area = match self.block.take() {
+            Some(b) => {
+                let inner_area = b.inner(area);
+
+                b.render(area, buf);
+
+                inner_area
+            }
+
+            None => area,
+        };
+
+        let height = text_area.height as usize;
+        let history_to_show = &self.entries[self.entries.len().saturating_sub(height)..];
+
+        for (y, entry) in history_to_show.iter().enumerate() {
+            let level = entry.level;
+            let level_color = match level {
+                Level::Error => Color::Red,
+                Level::Warn => Color::Yellow,
+                Level::Info => Color::Green,
+                Level::Debug => Color::Blue,
+                Level::Trace => Color::Cyan,
+            };
+
+            let timestamp = entry.time.time().format(format_description!("[[[hour padding:zero]:[minute padding:zero]:[second padding:zero].[subsecond digits:3]]")).unwrap_or(String::from("INVALID TIME"));
+
+            buf.set_line(
+                text_area.left(),
+                text_area.top() + y as u16,
+                &Line::from(vec![
+                    Span::from(format!("{timestamp} ")),
+                    Span::from(format!("{level:<5}: ")).bold().fg(level_color),
+                    Span::from(entry.message.as_str()),
+                ]),
+                text_area.width,
+            );
+        }
+    }
+}
+
+/// Records every message it's given; `Logger` decides which ones
+impl Log for TuiLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let timestamp =
+            time::OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+
+        let Ok(mut history) = self.state.lock() else {
+            return;
+        };
+
+        history.push(record.level(), timestamp, record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod test {
+    use log::Level;
+    use time::OffsetDateTime;
+
+    use super::{LogFilter, TuiLoggerState, HISTORY};
+    use crate::app::log_pane::LogPane;
+
+    #[test]
+    fn bounded_history_and_scrollback() {
+        let mut state = TuiLoggerState::default();
+        for i in 0..HISTORY + 10 {
+            let level = if i % 2 == 0 {
+                Level::Debug
+            } else {
+                Level::Info
+            };
+            state.push(level, OffsetDateTime::UNIX_EPOCH, format!("line {i}"));
+        }
+        assert_eq!(state.entries.len(), HISTORY);
+        assert_eq!(state.entries[0].id, 10);
+
+        let mut pane = LogPane::default();
+        pane.set_height(5);
+        pane.cycle_level();
+        pane.cycle_level();
+        assert_eq!(state.filtered(pane.filter()).count(), HISTORY / 2);
+
+        // Scrolling back keeps the same lines in view as new ones come in
+        pane.scroll(&state, 2);
+        let anchor = pane.anchor().unwrap();
+        state.push(Level::Info, OffsetDateTime::UNIX_EPOCH, "new".to_string());
+        pane.scroll(&state, 0);
+        assert_eq!(pane.anchor(), Some(anchor));
+
+        pane.scroll(&state, -10);
+        assert_eq!(pane.anchor(), None);
+
+        pane.scroll_to_top(&state);
+        let top: Vec<_> = state
+            .filtered(pane.filter())
+            .take(5)
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(pane.anchor(), top.last().copied());
+
+        let visible = state.visible(pane.filter(), pane.anchor(), 5);
+        assert_eq!(visible.iter().map(|e| e.id).collect::<Vec<_>>(), top);
+    }
+
+    #[test]
+    fn search_ignores_case() {
+        let mut state = TuiLoggerState::default();
+        for message in ["GO SQ1", "Loaded cue sq2", "Stopping all"] {
+            state.push(Level::Info, OffsetDateTime::UNIX_EPOCH, message.to_string());
+        }
+
+        let mut filter = LogFilter::default();
+        filter.set_search("Sq");
+        let found: Vec<_> = state
+            .visible(&filter, None, 5)
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(found, ["GO SQ1", "Loaded cue sq2"]);
+        assert_eq!(filter.search(), "Sq");
+    }
+}
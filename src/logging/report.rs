@@ -0,0 +1,494 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::Level;
+use serde::Serialize;
+use time::{macros::format_description, OffsetDateTime};
+
+use crate::cues::Script;
+use crate::prelude::*;
+use crate::util::format;
+
+use super::{show_logs, LogLine};
+
+/// Target of the record logged for every GO once its cue has been executed,
+/// which is what a show report is read from
+const GO_TARGET: &str = "encue::go";
+
+/// Log that the cue `label` was fired, and whether executing it failed
+pub fn log_go(label: &str, failed: bool) {
+    if failed {
+        log::warn!(target: GO_TARGET, "FAILED {label}");
+    } else {
+        log::info!(target: GO_TARGET, "GO {label}");
+    }
+}
+
+/// Formats a show report can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Json,
+}
+
+impl ReportFormat {
+    /// Pick the format from the extension of `path`
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("md" | "markdown") => Ok(Self::Markdown),
+            Some("json") => Ok(Self::Json),
+            _ => Err(FatalError::UnknownReportFormat(path.to_path_buf())),
+        }
+    }
+}
+
+/// What happened during one performance, read back from its show log
+#[derive(Debug, Serialize)]
+pub struct ShowReport {
+    log: PathBuf,
+    #[serde(with = "time::serde::rfc3339")]
+    start: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    end: OffsetDateTime,
+    /// Whether encue was quit normally, rather than the log just stopping
+    finished: bool,
+    /// Number of earlier performances the cue times are compared with
+    compared_with: usize,
+    cues: Vec<FiredCue>,
+    /// Cues that were passed over without being fired
+    skipped: Vec<String>,
+    holds: Vec<Hold>,
+    errors: Vec<Problem>,
+}
+
+/// A GO, and how its timing compares with earlier performances
+#[derive(Debug, Serialize)]
+pub struct FiredCue {
+    label: String,
+    #[serde(with = "time::serde::rfc3339")]
+    time: OffsetDateTime,
+    /// Seconds since the first GO of the performance
+    show_time: f64,
+    /// Average show time in earlier performances, if it was fired in any
+    usual: Option<f64>,
+    /// Fired before a cue that comes after it in the cue list, or fired again
+    out_of_order: bool,
+    /// The cue couldn't be executed
+    failed: bool,
+}
+
+/// A wait between two GOs that was longer than usual
+#[derive(Debug, Serialize)]
+pub struct Hold {
+    after: String,
+    before: String,
+    #[serde(with = "time::serde::rfc3339")]
+    time: OffsetDateTime,
+    /// Seconds between the two GOs
+    length: f64,
+    /// Seconds longer than the usual wait
+    extra: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Problem {
+    #[serde(with = "time::serde::rfc3339")]
+    time: OffsetDateTime,
+    message: String,
+}
+
+/// A GO read back from a show log
+#[derive(Clone, Copy)]
+struct Go<'a> {
+    label: &'a str,
+    time: OffsetDateTime,
+    failed: bool,
+}
+
+/// The GOs in one show log, in the order they happened
+struct Gos<'a>(Vec<Go<'a>>);
+
+impl<'a> Gos<'a> {
+    fn new(lines: &'a [LogLine]) -> Self {
+        Self(
+            lines
+                .iter()
+                .filter(|line| line.target == GO_TARGET)
+                .filter_map(|line| {
+                    let (label, failed) = match line.message.strip_prefix("GO ") {
+                        Some(label) => (label, false),
+                        None => (line.message.strip_prefix("FAILED ")?, true),
+                    };
+                    Some(Go {
+                        label,
+                        time: line.time,
+                        failed,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Seconds from the first GO to the first time each cue was fired,
+    /// and from the GO before it
+    fn times(&self) -> HashMap<&'a str, (f64, f64)> {
+        let mut times = HashMap::new();
+        let Some(first) = self.0.first() else {
+            return times;
+        };
+
+        for (i, go) in self.0.iter().enumerate() {
+            let wait = i
+                .checked_sub(1)
+                .map_or(0.0, |p| secs(go.time - self.0[p].time));
+            times
+                .entry(go.label)
+                .or_insert((secs(go.time - first.time), wait));
+        }
+
+        times
+    }
+}
+
+impl ShowReport {
+    /// Report on the show log at `log`, comparing it with the logs from
+    /// earlier performances next to it
+    pub fn from_log(log: &Path, script: &Script, hold: f64) -> Result<Self> {
+        let lines = read_log(log)?;
+
+        let dir = log.parent().unwrap_or(Path::new(""));
+        let name = log.file_name();
+        let previous = show_logs(dir)?
+            .into_iter()
+            .take_while(|p| p.file_name() != name)
+            .map(|p| read_log(&p))
+            .collect::<Result<Vec<_>>>()?;
+
+        let labels: Vec<_> = script
+            .cuelist()
+            .iter()
+            .map(|c| c.label().to_string())
+            .collect();
+        Self::new(log, &lines, &previous, &labels, hold)
+    }
+
+    /// `labels` is the cue list in order, and a wait between GOs is a hold if it's
+    /// more than `hold` seconds longer than usual
+    pub fn new(
+        log: &Path,
+        lines: &[LogLine],
+        previous: &[Vec<LogLine>],
+        labels: &[String],
+        hold: f64,
+    ) -> Result<Self> {
+        let (Some(first), Some(last)) = (lines.first(), lines.last()) else {
+            return Err(FatalError::EmptyLog(log.to_path_buf()));
+        };
+
+        // Only performances where something was fired say anything about timing
+        let history: Vec<_> = previous
+            .iter()
+            .map(|lines| Gos::new(lines).times())
+            .filter(|times| !times.is_empty())
+            .collect();
+        let usual = |label: &str| {
+            let times: Vec<_> = history.iter().filter_map(|t| t.get(label)).collect();
+            let average = |f: fn(&(f64, f64)) -> f64| {
+                (!times.is_empty())
+                    .then(|| times.iter().map(|t| f(t)).sum::<f64>() / times.len() as f64)
+            };
+            (average(|t| t.0), average(|t| t.1))
+        };
+
+        let gos = Gos::new(lines);
+        let index = |label: &str| labels.iter().position(|l| l == label);
+
+        let mut cues = Vec::new();
+        let mut holds = Vec::new();
+        let mut fired = vec![false; labels.len()];
+        let mut furthest: Option<usize> = None;
+
+        for (i, go) in gos.0.iter().enumerate() {
+            let (usual_time, usual_wait) = usual(go.label);
+
+            let out_of_order = match index(go.label) {
+                Some(i) => {
+                    let repeated = fired[i];
+                    let backwards = furthest.is_some_and(|f| i < f);
+                    fired[i] = true;
+                    furthest = furthest.max(Some(i));
+                    repeated || backwards
+                }
+                None => false,
+            };
+
+            // Without history there's no usual wait to call a hold
+            let after = i.checked_sub(1).map(|p| gos.0[p]);
+            if let (Some(after), Some(usual_wait)) = (after, usual_wait) {
+                let length = secs(go.time - after.time);
+                let extra = length - usual_wait;
+                if extra > hold {
+                    holds.push(Hold {
+                        after: after.label.to_string(),
+                        before: go.label.to_string(),
+                        time: after.time,
+                        length,
+                        extra,
+                    });
+                }
+            }
+
+            cues.push(FiredCue {
+                label: go.label.to_string(),
+                time: go.time,
+                show_time: gos
+                    .0
+                    .first()
+                    .map_or(0.0, |first| secs(go.time - first.time)),
+                usual: usual_time,
+                out_of_order,
+                failed: go.failed,
+            });
+        }
+
+        let skipped = labels
+            .iter()
+            .zip(&fired)
+            .take(furthest.map_or(0, |f| f + 1))
+            .filter(|(_, fired)| !**fired)
+            .map(|(label, _)| label.clone())
+            .collect();
+
+        let errors = lines
+            .iter()
+            .filter(|line| line.level == Level::Error)
+            .map(|line| Problem {
+                time: line.time,
+                message: line.message.clone(),
+            })
+            .collect();
+
+        Ok(Self {
+            log: log.to_path_buf(),
+            start: first.time,
+            end: last.time,
+            finished: lines.iter().any(|l| l.message == "Quitting application"),
+            compared_with: history.len(),
+            cues,
+            skipped,
+            holds,
+            errors,
+        })
+    }
+
+    pub fn render(&self, format: ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Markdown => Ok(self.markdown()),
+            ReportFormat::Json => Ok(serde_json::to_string_pretty(self)? + "\n"),
+        }
+    }
+
+    fn markdown(&self) -> String {
+        let name = self.log.file_name().unwrap_or_default().to_string_lossy();
+        let cell = |s: &str| s.replace('|', "\\|");
+
+        let mut out = format!("# Show report: {name}\n\n");
+        out += &format!("- Started: {}\n", date_time(self.start));
+        out += &format!(
+            "- Ended: {} after {}\n",
+            date_time(self.end),
+            hh_mm_ss(secs(self.end - self.start))
+        );
+        if !self.finished {
+            out += "- The log stops without encue being quit; it may have crashed\n";
+        }
+        out += &format!(
+            "- Cue times compared with {} earlier performance{}\n",
+            self.compared_with,
+            if self.compared_with == 1 { "" } else { "s" }
+        );
+
+        out += "\n## Cues\n\n";
+        if self.cues.is_empty() {
+            out += "No cues were fired.\n";
+        } else {
+            out += "| Cue | Fired | Show time | Usual | Difference | Notes |\n";
+            out += "| --- | --- | --- | --- | --- | --- |\n";
+            for cue in &self.cues {
+                out += &format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    cell(&cue.label),
+                    clock_time(cue.time),
+                    hh_mm_ss(cue.show_time),
+                    cue.usual.map(hh_mm_ss).unwrap_or_default(),
+                    cue.usual
+                        .map(|u| format::difference(cue.show_time - u))
+                        .unwrap_or_default(),
+                    notes(cue),
+                );
+            }
+        }
+
+        if !self.skipped.is_empty() {
+            out += "\n## Skipped cues\n\n";
+            for label in &self.skipped {
+                out += &format!("- {label}\n");
+            }
+        }
+
+        if !self.holds.is_empty() {
+            out += "\n## Holds\n\n";
+            for hold in &self.holds {
+                out += &format!(
+                    "- {} between {} and {}, {} longer than usual, from {}\n",
+                    hh_mm_ss(hold.length),
+                    hold.after,
+                    hold.before,
+                    hh_mm_ss(hold.extra),
+                    clock_time(hold.time),
+                );
+            }
+        }
+
+        if !self.errors.is_empty() {
+            out += "\n## Errors\n\n";
+            for error in &self.errors {
+                out += &format!("- {} {}\n", clock_time(error.time), error.message);
+            }
+        }
+
+        out
+    }
+}
+
+fn notes(cue: &FiredCue) -> String {
+    let notes: Vec<_> = [(cue.out_of_order, "out of order"), (cue.failed, "failed")]
+        .into_iter()
+        .filter_map(|(note, text)| note.then_some(text))
+        .collect();
+    notes.join(", ")
+}
+
+/// The newest show log in `dir`
+pub fn latest_log(dir: &Path) -> Result<PathBuf> {
+    show_logs(dir)
+        .ok()
+        .and_then(|logs| logs.last().cloned())
+        .ok_or_else(|| FatalError::NoShowLogs(dir.to_path_buf()))
+}
+
+/// Every line of a show log, leaving out any that were cut short
+fn read_log(path: &Path) -> Result<Vec<LogLine>> {
+    let text = fs::read_to_string(path)?;
+    Ok(text.lines().filter_map(|l| l.parse().ok()).collect())
+}
+
+fn secs(d: time::Duration) -> f64 {
+    d.as_seconds_f64()
+}
+
+fn hh_mm_ss(secs: f64) -> String {
+    format::hh_mm_ss(std::time::Duration::from_secs_f64(secs.max(0.0)))
+}
+
+fn clock_time(t: OffsetDateTime) -> String {
+    t.format(format_description!("[hour]:[minute]:[second]"))
+        .unwrap_or_default()
+}
+
+fn date_time(t: OffsetDateTime) -> String {
+    t.format(format_description!(
+        "[year]-[month]-[day] [hour]:[minute]:[second]"
+    ))
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use log::Level;
+    use time::{Duration, OffsetDateTime};
+
+    use super::{ReportFormat, ShowReport, GO_TARGET};
+    use crate::logging::LogLine;
+
+    const APP: &str = "encue::app::state";
+
+    fn log(start: OffsetDateTime, events: &[(i64, Level, &str, &str)]) -> Vec<LogLine> {
+        events
+            .iter()
+            .map(|(secs, level, target, message)| LogLine {
+                time: start + Duration::seconds(*secs),
+                level: *level,
+                target: target.to_string(),
+                message: message.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn show_report() {
+        let labels = ["SQ1", "SQ2", "SQ3", "SQ4"].map(String::from);
+
+        let day = time::macros::datetime!(2023-07-28 19:30 -5);
+        let previous = log(
+            day,
+            &[
+                (0, Level::Info, GO_TARGET, "GO SQ1"),
+                (60, Level::Info, GO_TARGET, "GO SQ2"),
+                (90, Level::Info, GO_TARGET, "GO SQ3"),
+                (100, Level::Info, GO_TARGET, "GO SQ4"),
+            ],
+        );
+
+        let today = day + Duration::days(1);
+        let lines = log(
+            today,
+            &[
+                (-30, Level::Info, APP, "Logging initialized at debug"),
+                (0, Level::Info, GO_TARGET, "GO SQ1"),
+                (5, Level::Info, APP, "GO SQ4 at 00:00:05 show time"),
+                (70, Level::Info, GO_TARGET, "GO SQ3"),
+                (400, Level::Error, APP, "Error executing cue SQ2: no output"),
+                (400, Level::Warn, GO_TARGET, "FAILED SQ2"),
+            ],
+        );
+
+        let report =
+            ShowReport::new(Path::new("show.log"), &lines, &[previous], &labels, 120.0).unwrap();
+
+        assert_eq!(report.compared_with, 1);
+        assert!(!report.finished);
+        assert_eq!(report.cues.len(), 3);
+        assert_eq!(report.cues[1].show_time, 70.0);
+        assert_eq!(report.cues[1].usual, Some(90.0));
+        assert!(report.cues[2].out_of_order);
+        assert!(report.cues[2].failed);
+        assert!(report.skipped.is_empty());
+        assert_eq!(report.holds.len(), 1);
+        assert_eq!(
+            (report.holds[0].before.as_str(), report.holds[0].extra),
+            ("SQ2", 270.0)
+        );
+        assert_eq!(report.errors.len(), 1);
+
+        let markdown = report.render(ReportFormat::Markdown).unwrap();
+        assert!(markdown.contains("| SQ3 | 19:31:10 | 00:01:10 | 00:01:30 | -20s |  |"));
+        assert!(markdown
+            .contains("| SQ2 | 19:36:40 | 00:06:40 | 00:01:00 | +5:40 | out of order, failed |"));
+        assert!(markdown.contains("- 00:05:30 between SQ3 and SQ2, 00:04:30 longer than usual"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&report.render(ReportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["cues"][2]["out_of_order"], true);
+
+        // With nothing to compare against, no wait is longer than usual
+        let report = ShowReport::new(Path::new("show.log"), &lines, &[], &labels, 120.0).unwrap();
+        assert_eq!(report.compared_with, 0);
+        assert!(report.holds.is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 mod app;
+mod cli;
 mod cues;
 mod error;
 mod logging;
@@ -8,14 +9,23 @@ mod terminal;
 mod util;
 
 use app::{events::EventListener, update::update, AppState};
+use clap::Parser;
+use cli::Cli;
 use cues::Script;
 
 use crate::prelude::*;
 
 fn main() -> Result<()> {
-    let script = Script::load()?.validate()?;
+    let cli = Cli::parse();
+    let path = cli.script_path();
 
-    let mut app = AppState::new(&script)?;
+    if let Some(command) = cli.command {
+        return command.run(&path);
+    }
+
+    let script = Script::load_from(&path)?.validate()?;
+
+    let mut app = AppState::new(script, path, cli.log.unwrap_or_default(), cli.rehearsal)?;
 
     let events = EventListener::init();
 
@@ -0,0 +1,255 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
+
+use rodio::{decoder::DecoderError, Decoder, Source};
+
+/// Fully decoded audio held in memory and shared between every cue that plays it
+#[derive(Clone)]
+pub struct DecodedAudio {
+    samples: Arc<[f32]>,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl DecodedAudio {
+    fn decode(path: &Path) -> Result<Self, DecodeError> {
+        let decoder = Decoder::new(BufReader::new(File::open(path)?))?;
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+
+        Ok(Self {
+            samples: decoder.convert_samples().collect(),
+            channels,
+            sample_rate,
+        })
+    }
+
+    pub fn duration(&self) -> Duration {
+        let frames = self.samples.len() / self.channels.max(1) as usize;
+        Duration::from_secs_f64(frames as f64 / self.sample_rate.max(1) as f64)
+    }
+
+    fn size(&self) -> usize {
+        self.samples.len() * std::mem::size_of::<f32>()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    #[error(transparent)]
+    File(#[from] io::Error),
+
+    #[error("Decoder error: {0}")]
+    Decode(#[from] DecoderError),
+}
+
+/// Source that plays back a [`DecodedAudio`] buffer
+pub struct BufferSource {
+    audio: DecodedAudio,
+    pos: usize,
+}
+
+impl BufferSource {
+    pub fn new(audio: DecodedAudio) -> Self {
+        Self { audio, pos: 0 }
+    }
+}
+
+impl Iterator for BufferSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.audio.samples.get(self.pos).copied()?;
+        self.pos += 1;
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.audio.samples.len() - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl Source for BufferSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.audio.samples.len() - self.pos)
+    }
+
+    fn channels(&self) -> u16 {
+        self.audio.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.audio.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(self.audio.duration())
+    }
+}
+
+struct Entry {
+    audio: DecodedAudio,
+    last_used: u64,
+}
+
+/// Least-recently-used cache of decoded files, bounded by total size in bytes
+struct AudioCache {
+    budget: usize,
+    used: usize,
+    clock: u64,
+    entries: HashMap<PathBuf, Entry>,
+}
+
+impl AudioCache {
+    fn get(&mut self, path: &Path) -> Option<DecodedAudio> {
+        self.clock += 1;
+
+        let entry = self.entries.get_mut(path)?;
+        entry.last_used = self.clock;
+
+        Some(entry.audio.clone())
+    }
+
+    fn insert(&mut self, path: &Path, audio: DecodedAudio) {
+        let size = audio.size();
+
+        if size > self.budget {
+            log::warn!(
+                "`{}` is larger than the audio cache; it will be decoded on every load",
+                path.display()
+            );
+            return;
+        }
+
+        self.make_room(size);
+        self.clock += 1;
+        self.used += size;
+
+        let previous = self.entries.insert(
+            path.to_path_buf(),
+            Entry {
+                audio,
+                last_used: self.clock,
+            },
+        );
+
+        if let Some(previous) = previous {
+            self.used -= previous.audio.size();
+        }
+    }
+
+    fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.make_room(0);
+    }
+
+    /// Evict the least recently used entries until `size` more bytes fit in the budget
+    fn make_room(&mut self, size: usize) {
+        while self.used + size > self.budget {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(p, _)| p.clone())
+            else {
+                break;
+            };
+
+            if let Some(evicted) = self.entries.remove(&oldest) {
+                log::debug!("Evicted `{}` from the audio cache", oldest.display());
+                self.used -= evicted.audio.size();
+            }
+        }
+    }
+}
+
+fn cache() -> &'static Mutex<AudioCache> {
+    static CACHE: OnceLock<Mutex<AudioCache>> = OnceLock::new();
+
+    CACHE.get_or_init(|| {
+        Mutex::new(AudioCache {
+            budget: crate::util::defaults::default_cache_budget() as usize * 1024 * 1024,
+            used: 0,
+            clock: 0,
+            entries: HashMap::new(),
+        })
+    })
+}
+
+/// Limit the total size of decoded audio kept in memory to `megabytes`
+pub fn set_budget(megabytes: u32) {
+    if let Ok(mut cache) = cache().lock() {
+        cache.set_budget(megabytes as usize * 1024 * 1024);
+    }
+}
+
+/// Get the decoded contents of `path`, decoding and caching it if necessary
+pub fn load(path: &Path) -> Result<DecodedAudio, DecodeError> {
+    if let Some(audio) = cache().lock().ok().and_then(|mut c| c.get(path)) {
+        return Ok(audio);
+    }
+
+    // Decode without holding the lock so other cues can load in the meantime
+    let audio = DecodedAudio::decode(path)?;
+    log::debug!(
+        "Decoded `{}` into memory ({:.1} MB)",
+        path.display(),
+        audio.size() as f32 / (1024.0 * 1024.0)
+    );
+
+    if let Ok(mut cache) = cache().lock() {
+        cache.insert(path, audio.clone());
+    }
+
+    Ok(audio)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn audio(len: usize) -> DecodedAudio {
+        DecodedAudio {
+            samples: vec![0.0; len].into(),
+            channels: 1,
+            sample_rate: 1,
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = AudioCache {
+            budget: 3 * 4,
+            used: 0,
+            clock: 0,
+            entries: HashMap::new(),
+        };
+
+        cache.insert(Path::new("a"), audio(1));
+        cache.insert(Path::new("b"), audio(1));
+        cache.insert(Path::new("c"), audio(1));
+        assert!(cache.get(Path::new("a")).is_some());
+
+        cache.insert(Path::new("d"), audio(1));
+
+        assert!(cache.get(Path::new("b")).is_none());
+        assert!(cache.get(Path::new("a")).is_some());
+        assert_eq!(cache.used, 3 * 4);
+
+        cache.insert(Path::new("e"), audio(4));
+        assert!(cache.get(Path::new("e")).is_none());
+
+        // Lowering the budget evicts straight away
+        cache.set_budget(4);
+        assert_eq!(cache.used, 4);
+        assert!(cache.get(Path::new("a")).is_some());
+        assert!(cache.get(Path::new("d")).is_none());
+    }
+}
@@ -3,20 +3,36 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use rodio::{OutputStream, OutputStreamHandle, Sink};
+use rodio::{
+    cpal::traits::{DeviceTrait, HostTrait},
+    dynamic_mixer::{self, DynamicMixerController},
+    source::Zero,
+    OutputStream, OutputStreamHandle, Sink,
+};
 
 use crate::prelude::*;
 
-use super::metadata::PlaybackMeta;
+use super::{
+    metadata::PlaybackMeta,
+    meter::{Meter, Metered},
+    spectrum::{SampleHistory, Tapped},
+};
+
+/// Output format used if the default device can't be queried
+const FALLBACK_FORMAT: (u16, u32) = (2, 44_100);
 
 struct ActiveCue {
     sink: Arc<Sink>,
     meta: Arc<Mutex<PlaybackMeta>>,
+    history: SampleHistory,
 }
 
 pub struct AudioEngine {
     _output_stream: OutputStream,
     output_handle: OutputStreamHandle,
+    mixer: Arc<DynamicMixerController<f32>>,
+    meter: Meter,
+    history: SampleHistory,
     sinks: HashMap<String, ActiveCue>,
 }
 
@@ -25,9 +41,31 @@ impl AudioEngine {
     pub fn try_init_default() -> Result<Self> {
         let (_output_stream, output_handle) = OutputStream::try_default()?;
 
+        let (channels, sample_rate) = rodio::cpal::default_host()
+            .default_output_device()
+            .and_then(|d| d.default_output_config().ok())
+            .map_or(FALLBACK_FORMAT, |c| (c.channels(), c.sample_rate().0));
+
+        // All cues are mixed down here before reaching the output, so that
+        // the master signal can be metered
+        let (mixer, output) = dynamic_mixer::mixer(channels, sample_rate);
+
+        // The mixer ends as soon as it runs out of sources, so keep silence playing
+        mixer.add(Zero::<f32>::new(channels, sample_rate));
+
+        let meter = Meter::new(channels);
+        let history = SampleHistory::default();
+        output_handle.play_raw(Metered::new(
+            Tapped::new(output, history.clone()),
+            meter.clone(),
+        ))?;
+
         Ok(Self {
             _output_stream,
             output_handle,
+            mixer,
+            meter,
+            history,
             sinks: HashMap::new(),
         })
     }
@@ -36,14 +74,47 @@ impl AudioEngine {
         &self.output_handle
     }
 
+    pub fn mixer(&self) -> &DynamicMixerController<f32> {
+        &self.mixer
+    }
+
+    pub fn meter(&self) -> &Meter {
+        &self.meter
+    }
+
+    /// Recent samples of the master output, or of the active cue `label`
+    pub fn history(&self, label: Option<&str>) -> Option<&SampleHistory> {
+        match label {
+            Some(l) => self.sinks.get(l).map(|s| &s.history),
+            None => Some(&self.history),
+        }
+    }
+
     pub fn get_sink(&self, k: &str) -> Option<Arc<Sink>> {
         self.sinks.get(k).and_then(|f| Some(f.sink.clone()))
     }
 
-    pub fn add_sink(&mut self, k: impl ToString, sink: Sink, meta: Arc<Mutex<PlaybackMeta>>) {
+    pub fn get_meta(&self, k: &str) -> Option<Arc<Mutex<PlaybackMeta>>> {
+        self.sinks.get(k).map(|f| f.meta.clone())
+    }
+
+    pub fn add_sink(
+        &mut self,
+        k: impl ToString,
+        sink: Sink,
+        meta: Arc<Mutex<PlaybackMeta>>,
+        history: SampleHistory,
+    ) {
         let sink = Arc::new(sink);
 
-        self.sinks.insert(k.to_string(), ActiveCue { sink, meta });
+        self.sinks.insert(
+            k.to_string(),
+            ActiveCue {
+                sink,
+                meta,
+                history,
+            },
+        );
     }
 
     pub fn take_sink(&mut self, k: &str) -> Option<Arc<Sink>> {
@@ -60,6 +131,18 @@ impl AudioEngine {
         self.sinks.iter().map(|(k, v)| (k.as_str(), &v.meta))
     }
 
+    /// Label, current volume and playback metadata of each active cue, sorted by label
+    pub fn active(&self) -> Vec<(&str, f32, &Arc<Mutex<PlaybackMeta>>)> {
+        let mut active: Vec<_> = self
+            .sinks
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.sink.volume(), &v.meta))
+            .collect();
+
+        active.sort_by_key(|(k, _, _)| *k);
+        active
+    }
+
     pub fn gc(&mut self) {
         self.sinks.retain(|_, s| !s.sink.empty())
     }
@@ -3,4 +3,42 @@ use std::time::{Duration, Instant};
 pub struct PlaybackMeta {
     pub start: Instant,
     pub duration: Duration,
+    pub fade_in: Option<Duration>,
+    pub fade_out: Option<Duration>,
+    pub repeat: bool,
+    pub loops: u32,
+    pub track: Option<String>,
+    pub fading: bool,
+}
+
+impl PlaybackMeta {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            start: Instant::now(),
+            duration,
+            fade_in: None,
+            fade_out: None,
+            repeat: false,
+            loops: 0,
+            track: None,
+            fading: false,
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed().min(self.duration)
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed())
+    }
+
+    /// Whether the volume is currently being changed by a fade in, fade out or fade cue
+    pub fn is_fading(&self) -> bool {
+        let elapsed = self.elapsed();
+
+        self.fading
+            || (self.loops == 0 && self.fade_in.is_some_and(|d| elapsed < d))
+            || self.fade_out.is_some_and(|d| self.remaining() < d)
+    }
 }
@@ -0,0 +1,187 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use rodio::Source;
+
+/// How long a peak stays held before it's allowed to fall
+const PEAK_HOLD: Duration = Duration::from_secs(2);
+
+/// How long the clip indicator stays lit after a clipped sample
+const CLIP_HOLD: Duration = Duration::from_secs(3);
+
+/// Number of times per second that levels are published to the UI
+const UPDATE_RATE: u32 = 30;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelLevel {
+    pub peak: f32,
+    pub rms: f32,
+    pub hold: f32,
+    hold_at: Instant,
+    clip_at: Option<Instant>,
+}
+
+impl Default for ChannelLevel {
+    fn default() -> Self {
+        Self {
+            peak: 0.0,
+            rms: 0.0,
+            hold: 0.0,
+            hold_at: Instant::now(),
+            clip_at: None,
+        }
+    }
+}
+
+impl ChannelLevel {
+    pub fn clipped(&self) -> bool {
+        self.clip_at.is_some_and(|t| t.elapsed() < CLIP_HOLD)
+    }
+
+    fn update(&mut self, peak: f32, rms: f32) {
+        let now = Instant::now();
+
+        self.peak = peak;
+        self.rms = rms;
+
+        if peak >= self.hold || now.duration_since(self.hold_at) > PEAK_HOLD {
+            self.hold = peak;
+            self.hold_at = now;
+        }
+
+        if peak >= 1.0 {
+            self.clip_at = Some(now);
+        }
+    }
+}
+
+/// Shared handle to the most recent output levels, one entry per channel
+#[derive(Clone)]
+pub struct Meter(Arc<Mutex<Vec<ChannelLevel>>>);
+
+impl Meter {
+    pub fn new(channels: u16) -> Self {
+        Self(Arc::new(Mutex::new(vec![
+            ChannelLevel::default();
+            channels as usize
+        ])))
+    }
+
+    pub fn levels(&self) -> Vec<ChannelLevel> {
+        self.0.lock().map(|l| l.clone()).unwrap_or_default()
+    }
+}
+
+/// Source adapter that measures the levels of everything passing through it
+pub struct Metered<S> {
+    inner: S,
+    meter: Meter,
+    channel: usize,
+    frames: u32,
+    window: u32,
+    peaks: Vec<f32>,
+    squares: Vec<f32>,
+}
+
+impl<S: Source<Item = f32>> Metered<S> {
+    pub fn new(inner: S, meter: Meter) -> Self {
+        let channels = inner.channels() as usize;
+        let window = (inner.sample_rate() / UPDATE_RATE).max(1);
+
+        Self {
+            inner,
+            meter,
+            channel: 0,
+            frames: 0,
+            window,
+            peaks: vec![0.0; channels],
+            squares: vec![0.0; channels],
+        }
+    }
+
+    fn publish(&mut self) {
+        if let Ok(mut levels) = self.meter.0.try_lock() {
+            let n = self.frames as f32;
+
+            for (level, (peak, square)) in levels
+                .iter_mut()
+                .zip(self.peaks.iter().zip(self.squares.iter()))
+            {
+                level.update(*peak, (square / n).sqrt());
+            }
+        }
+
+        self.frames = 0;
+        self.peaks.fill(0.0);
+        self.squares.fill(0.0);
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Metered<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.inner.next()?;
+
+        if let (Some(peak), Some(square)) = (
+            self.peaks.get_mut(self.channel),
+            self.squares.get_mut(self.channel),
+        ) {
+            *peak = peak.max(sample.abs());
+            *square += sample * sample;
+        }
+
+        self.channel += 1;
+        if self.channel >= self.peaks.len() {
+            self.channel = 0;
+            self.frames += 1;
+
+            if self.frames >= self.window {
+                self.publish();
+            }
+        }
+
+        Some(sample)
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Metered<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rodio::buffer::SamplesBuffer;
+
+    use super::*;
+
+    #[test]
+    fn measures_levels() {
+        let samples: Vec<f32> = (0..4410).flat_map(|_| [0.5, -1.0]).collect();
+        let meter = Meter::new(2);
+
+        Metered::new(SamplesBuffer::new(2, 44_100, samples), meter.clone()).for_each(drop);
+
+        let levels = meter.levels();
+        assert!((levels[0].peak - 0.5).abs() < f32::EPSILON);
+        assert!((levels[0].rms - 0.5).abs() < 1e-4);
+        assert!(!levels[0].clipped());
+        assert!(levels[1].clipped());
+    }
+}
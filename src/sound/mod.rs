@@ -1,7 +1,10 @@
+pub mod cache;
 mod engine;
 mod executable;
 pub mod metadata;
+pub mod meter;
 mod playback;
+pub mod spectrum;
 
 pub use engine::AudioEngine;
 pub use executable::{ExecutableCue, ExecuteCue, ExecuteCueError, PrepareCue};
@@ -2,7 +2,11 @@ use std::sync::{Arc, Mutex};
 
 use rodio::{queue::SourcesQueueOutput, Sink};
 
-use super::{metadata::PlaybackMeta, ExecuteCue, ExecuteCueError};
+use super::{
+    metadata::PlaybackMeta,
+    spectrum::{SampleHistory, Tapped},
+    ExecuteCue, ExecuteCueError,
+};
 
 pub struct PlaybackExecutable {
     label: Option<String>,
@@ -29,10 +33,12 @@ impl PlaybackExecutable {
 
 impl ExecuteCue for PlaybackExecutable {
     fn execute(self, engine: &mut super::AudioEngine) -> Result<(), ExecuteCueError> {
-        engine.output_handle().play_raw(self.queue)?;
+        let history = SampleHistory::default();
+
+        engine.mixer().add(Tapped::new(self.queue, history.clone()));
         self.sink.play();
         if let Some(label) = self.label {
-            engine.add_sink(label, self.sink, self.meta)
+            engine.add_sink(label, self.sink, self.meta, history)
         } else {
             self.sink.detach()
         }
@@ -0,0 +1,292 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rodio::Source;
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+
+/// Number of samples analyzed per spectrum frame
+pub const FFT_SIZE: usize = 4096;
+
+/// Number of frames buffered by a tap before it publishes them
+const TAP_CHUNK: usize = 512;
+
+/// Lowest level reported by the analyzer, in dBFS
+pub const FLOOR_DB: f32 = -90.0;
+
+const MIN_FREQ: f32 = 20.0;
+const MAX_FREQ: f32 = 20_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Resolution {
+    #[default]
+    ThirdOctave,
+    Linear,
+}
+
+impl Resolution {
+    pub fn toggle(self) -> Self {
+        match self {
+            Resolution::ThirdOctave => Resolution::Linear,
+            Resolution::Linear => Resolution::ThirdOctave,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Resolution::ThirdOctave => "1/3 oct",
+            Resolution::Linear => "linear",
+        }
+    }
+}
+
+struct History {
+    samples: VecDeque<f32>,
+    sample_rate: u32,
+}
+
+/// The most recent samples (mixed to mono) that passed through a [`Tapped`] source
+#[derive(Clone)]
+pub struct SampleHistory(Arc<Mutex<History>>);
+
+impl Default for SampleHistory {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(History {
+            samples: VecDeque::with_capacity(FFT_SIZE),
+            sample_rate: 0,
+        })))
+    }
+}
+
+impl SampleHistory {
+    /// Copy out the last [`FFT_SIZE`] samples, if that many have been recorded
+    pub fn snapshot(&self) -> Option<(Vec<f32>, u32)> {
+        let history = self.0.lock().ok()?;
+
+        if history.samples.len() < FFT_SIZE {
+            return None;
+        }
+
+        Some((
+            history.samples.iter().copied().collect(),
+            history.sample_rate,
+        ))
+    }
+}
+
+/// Source adapter that records everything passing through it into a [`SampleHistory`]
+pub struct Tapped<S> {
+    inner: S,
+    history: SampleHistory,
+    channel: u16,
+    frame_sum: f32,
+    pending: Vec<f32>,
+}
+
+impl<S: Source<Item = f32>> Tapped<S> {
+    pub fn new(inner: S, history: SampleHistory) -> Self {
+        Self {
+            inner,
+            history,
+            channel: 0,
+            frame_sum: 0.0,
+            pending: Vec::with_capacity(TAP_CHUNK),
+        }
+    }
+
+    fn publish(&mut self) {
+        let Ok(mut history) = self.history.0.try_lock() else {
+            // Drop the oldest frames rather than block the audio thread
+            if self.pending.len() > FFT_SIZE {
+                self.pending.drain(..self.pending.len() - FFT_SIZE);
+            }
+            return;
+        };
+
+        history.sample_rate = self.inner.sample_rate();
+        history.samples.extend(self.pending.drain(..));
+
+        let excess = history.samples.len().saturating_sub(FFT_SIZE);
+        history.samples.drain(..excess);
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Tapped<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.inner.next()?;
+        let channels = self.inner.channels().max(1);
+
+        self.frame_sum += sample;
+        self.channel += 1;
+
+        if self.channel >= channels {
+            self.pending.push(self.frame_sum / channels as f32);
+            self.channel = 0;
+            self.frame_sum = 0.0;
+
+            if self.pending.len() >= TAP_CHUNK {
+                self.publish();
+            }
+        }
+
+        Some(sample)
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Tapped<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
+
+pub struct SpectrumAnalyzer {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+}
+
+impl Default for SpectrumAnalyzer {
+    fn default() -> Self {
+        let fft = FftPlanner::new().plan_fft_forward(FFT_SIZE);
+
+        // Hann window
+        let window = (0..FFT_SIZE)
+            .map(|i| {
+                let phase = std::f32::consts::TAU * i as f32 / FFT_SIZE as f32;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect();
+
+        Self { fft, window }
+    }
+}
+
+impl SpectrumAnalyzer {
+    /// Compute the level of each of (up to) `bars` frequency bands, in dBFS
+    pub fn analyze(
+        &self,
+        history: &SampleHistory,
+        resolution: Resolution,
+        bars: usize,
+    ) -> Vec<f32> {
+        let Some((samples, sample_rate)) = history.snapshot() else {
+            return Vec::new();
+        };
+
+        let mut buffer: Vec<_> = samples
+            .iter()
+            .zip(&self.window)
+            .map(|(s, w)| Complex::new(s * w, 0.0))
+            .collect();
+
+        self.fft.process(&mut buffer);
+
+        // A full scale sine comes out of a Hann windowed FFT at N/4
+        let scale = 4.0 / FFT_SIZE as f32;
+        let power: Vec<f32> = buffer[..FFT_SIZE / 2]
+            .iter()
+            .map(|c| (c.norm() * scale).powi(2))
+            .collect();
+
+        let bin_width = sample_rate as f32 / FFT_SIZE as f32;
+
+        band_edges(resolution, bars)
+            .into_iter()
+            .map(|(low, high)| {
+                let first = (low / bin_width).ceil() as usize;
+                let last = ((high / bin_width).ceil() as usize).max(first + 1);
+
+                let sum: f32 = power
+                    .get(first..last.min(power.len()))
+                    .unwrap_or(&[])
+                    .iter()
+                    .sum();
+
+                (10.0 * sum.log10()).max(FLOOR_DB)
+            })
+            .collect()
+    }
+}
+
+/// Lower and upper frequency of each band to display
+fn band_edges(resolution: Resolution, bars: usize) -> Vec<(f32, f32)> {
+    match resolution {
+        Resolution::ThirdOctave => {
+            let edge = 2_f32.powf(1.0 / 6.0);
+
+            // Standard 1/3 octave centers from 25Hz to 20kHz
+            let bands: Vec<_> = (-16..=13)
+                .map(|n| 1000.0 * 2_f32.powf(n as f32 / 3.0))
+                .map(|center| (center / edge, center * edge))
+                .collect();
+
+            // Merge neighbouring bands when there isn't room for all of them
+            let per_bar = bands.len().div_ceil(bars.max(1));
+            bands
+                .chunks(per_bar)
+                .map(|chunk| (chunk[0].0, chunk[chunk.len() - 1].1))
+                .collect()
+        }
+        Resolution::Linear => {
+            let bars = bars.max(1);
+            let width = (MAX_FREQ - MIN_FREQ) / bars as f32;
+
+            (0..bars)
+                .map(|i| {
+                    let low = MIN_FREQ + width * i as f32;
+                    (low, low + width)
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rodio::source::SineWave;
+
+    use super::*;
+
+    #[test]
+    fn finds_sine_band() {
+        let history = SampleHistory::default();
+        let sine = SineWave::new(1000.0).take_duration(Duration::from_millis(500));
+
+        Tapped::new(sine, history.clone()).for_each(drop);
+
+        let analyzer = SpectrumAnalyzer::default();
+        let loudest = |bands: &[f32]| {
+            bands
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(i, _)| i)
+        };
+
+        // 1kHz is the 17th band starting from 25Hz
+        let bands = analyzer.analyze(&history, Resolution::ThirdOctave, 80);
+        assert_eq!(bands.len(), 30);
+        assert_eq!(loudest(&bands), Some(16));
+        assert!(bands[16] > -3.0);
+
+        // With room for 10 bars, every 3 bands are merged
+        let bands = analyzer.analyze(&history, Resolution::ThirdOctave, 10);
+        assert_eq!(bands.len(), 10);
+        assert_eq!(loudest(&bands), Some(5));
+    }
+}
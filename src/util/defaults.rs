@@ -5,3 +5,22 @@ pub fn default_vol() -> u8 {
 pub fn default_fade_duration() -> std::time::Duration {
     std::time::Duration::from_secs(5)
 }
+
+pub fn default_go_lockout() -> std::time::Duration {
+    std::time::Duration::from_millis(500)
+}
+
+/// Memory budget of the decoded audio cache, in megabytes
+pub fn default_cache_budget() -> u32 {
+    256
+}
+
+/// Folder show logs are written to, relative to the script
+pub fn default_log_dir() -> std::path::PathBuf {
+    "logs".into()
+}
+
+/// Number of show logs kept before the oldest are deleted
+pub fn default_log_keep() -> usize {
+    30
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+/// Format a duration as a short number of seconds, e.g. `5s` or `2.5s`
+pub fn secs(d: Duration) -> String {
+    let secs = d.as_secs_f32();
+
+    if secs.fract() == 0.0 {
+        format!("{secs:.0}s")
+    } else {
+        format!("{secs:.1}s")
+    }
+}
+
+/// Format a duration as hours, minutes and seconds, e.g. `01:02:03`
+pub fn hh_mm_ss(d: Duration) -> String {
+    let secs = d.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Format a duration as minutes and seconds, e.g. `03:07`
+pub fn mm_ss(d: Duration) -> String {
+    let secs = d.as_secs();
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+/// Format seconds later than expected, or earlier if negative, e.g. `+12s` or `-1:05`
+pub fn difference(secs: f64) -> String {
+    let sign = if secs < 0.0 { '-' } else { '+' };
+    let secs = secs.abs().round() as u64;
+
+    if secs < 60 {
+        format!("{sign}{secs}s")
+    } else {
+        format!("{sign}{}:{:02}", secs / 60, secs % 60)
+    }
+}
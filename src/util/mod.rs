@@ -1,2 +1,4 @@
 pub mod defaults;
+pub mod format;
+pub mod fs;
 pub mod serde;
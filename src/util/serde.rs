@@ -1,11 +1,49 @@
 pub mod string_or_struct {
     use std::{convert::Infallible, marker::PhantomData, str::FromStr};
 
+    use schemars::{
+        gen::SchemaGenerator,
+        schema::{Schema, SchemaObject, SubschemaValidation},
+        JsonSchema,
+    };
     use serde::{
         de::{self, Visitor},
-        Deserialize, Deserializer,
+        Deserialize, Deserializer, Serialize, Serializer,
     };
 
+    /// Types that can be written as a single string when only their main field is set
+    pub trait ShortForm {
+        fn short_form(&self) -> Option<String>;
+    }
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize + ShortForm,
+        S: Serializer,
+    {
+        match value.short_form() {
+            Some(s) => serializer.serialize_str(&s),
+            None => value.serialize(serializer),
+        }
+    }
+
+    /// For `schemars(schema_with)`: either the short string or the full struct
+    pub fn schema<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+        let subschemas = SubschemaValidation {
+            any_of: Some(vec![
+                gen.subschema_for::<String>(),
+                gen.subschema_for::<T>(),
+            ]),
+            ..Default::default()
+        };
+
+        SchemaObject {
+            subschemas: Some(Box::new(subschemas)),
+            ..Default::default()
+        }
+        .into()
+    }
+
     pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
     where
         T: Deserialize<'de> + FromStr<Err = Infallible>,
@@ -38,3 +76,55 @@ pub mod string_or_struct {
         deserializer.deserialize_any(StringOrStructVisitor(PhantomData))
     }
 }
+
+/// For `skip_serializing_if`, to leave out fields that are at their default value
+pub fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// (De)serialize an optional wall-clock time of day written as `HH:MM` or `HH:MM:SS`
+pub mod time_of_day {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use time::Time;
+
+    pub fn parse(s: &str) -> Option<Time> {
+        let mut parts = s.trim().split(':').map(str::parse::<u8>);
+
+        let hour = parts.next()?.ok()?;
+        let minute = parts.next()?.ok()?;
+        let second = match parts.next() {
+            Some(s) => s.ok()?,
+            None => 0,
+        };
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Time::from_hms(hour, minute, second).ok()
+    }
+
+    pub fn serialize<S: Serializer>(t: &Option<Time>, serializer: S) -> Result<S::Ok, S::Error> {
+        match t {
+            Some(t) => serializer.serialize_str(&format!(
+                "{:02}:{:02}:{:02}",
+                t.hour(),
+                t.minute(),
+                t.second()
+            )),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Time>, D::Error> {
+        let Some(s) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+
+        parse(&s)
+            .map(Some)
+            .ok_or_else(|| de::Error::custom(format!("invalid time of day `{s}`, expected HH:MM")))
+    }
+}